@@ -18,7 +18,7 @@ fn generate_styles() {
     let mut outfile = fs::File::create(outfile_path).unwrap();
     writeln!(
         outfile,
-        r#"/// Automatically generated styles.
+        r#"// Automatically generated styles.
 
 use crate::goban_style::GobanStyle;"#
     )
@@ -102,7 +102,7 @@ fn generate_tests() {
 fn write_tests_header(outfile: &mut fs::File) {
     write!(
         outfile,
-        r#"/// Automatically generated tests.
+        r#"// Automatically generated tests.
 
 use clap::Parser;
 
@@ -1,13 +1,20 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use clap::builder::styling::{AnsiColor, Styles};
 use clap::Parser;
 
 use crate::board_side::BoardSideSet;
+use crate::board_transform::BoardTransform;
+use crate::coordinates::CoordinateStyle;
 use crate::errors::UsageError;
+use crate::game_info::ResultFormat;
 use crate::generated_styles;
 use crate::goban_range::GobanRange;
-use crate::make_svg::{MakeSvgOptions, MoveNumberOptions};
+use crate::make_svg::{
+    ArrowStyle, BackgroundShape, BoardCorner, BoardTexture, MakeSvgOptions, MarkupKind,
+    MoveNumberDisplay, MoveNumberOptions, ShapeRendering, SizeSpec, SolutionKind,
+};
 use crate::node_description::NodeDescription;
 
 // clap v3 styling
@@ -23,6 +30,9 @@ pub struct SgfRenderArgs {
     /// SGF file to render [default: read from stdin].
     #[arg(value_name = "FILE")]
     pub infile: Option<PathBuf>,
+    /// Input format [default: gib for files ending in '.gib', otherwise sgf].
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    pub input_format: Option<InputFormat>,
     /// Output file [default: write to stdout].
     #[arg(short, long, value_name = "FILE")]
     pub outfile: Option<PathBuf>,
@@ -30,6 +40,19 @@ pub struct SgfRenderArgs {
     #[arg(short = 'f', long = "format", default_value = "svg")]
     #[cfg_attr(not(feature = "png"), arg(hide = true))]
     pub output_format: OutputFormat,
+    /// Resolution of PNG and WebP output (96 gives one pixel per unit of width).
+    #[arg(long, default_value_t = 96.0)]
+    #[cfg_attr(not(feature = "png"), arg(hide = true))]
+    pub dpi: f64,
+    /// Delay between frames of GIF output in milliseconds. GIF output animates the main
+    /// variation.
+    #[arg(long, value_name = "MS", default_value_t = 500)]
+    #[cfg_attr(not(feature = "gif"), arg(hide = true))]
+    pub frame_delay: u16,
+    /// Quality of WebP output, from 0 to 100.
+    #[arg(long, value_name = "QUALITY", default_value_t = 80.0)]
+    #[cfg_attr(not(feature = "webp"), arg(hide = true))]
+    pub webp_quality: f32,
     #[clap(flatten)]
     pub make_svg_args: MakeSvgArgs,
 }
@@ -40,6 +63,12 @@ pub struct MakeSvgArgs {
     /// the last node. See the README for more detail.
     #[arg(short, long = "node", value_name = "PATH_SPEC")]
     node_description: Option<NodeDescription>,
+    /// Index of the game to render in a file with multiple games (starting at 0).
+    #[arg(long = "game", value_name = "INDEX", default_value_t = 0)]
+    game_index: usize,
+    /// Fail if the SGF places stones or markup outside the board, instead of leaving them out.
+    #[arg(long)]
+    strict: bool,
     /// Width of the output image in pixels.
     #[arg(
         short = 'w',
@@ -48,18 +77,118 @@ pub struct MakeSvgArgs {
         default_value_t = 800.0
     )]
     viewbox_width: f64,
+    /// Height of the output image in pixels. The diagram is centered if it doesn't fill it.
+    #[arg(long = "height", value_name = "HEIGHT")]
+    viewbox_height: Option<f64>,
+    /// Width of each board cell in pixels, instead of fixing the width of the whole image, so
+    /// diagrams of different board sizes share a scale.
+    #[arg(long, value_name = "SIZE", conflicts_with = "viewbox_width")]
+    cell_size: Option<f64>,
+    /// Draw only the board and stones if the output is narrower than this many pixels, for
+    /// thumbnails.
+    #[arg(long = "simplify-below", value_name = "WIDTH")]
+    simplify_below_width: Option<f64>,
+    /// Space between the outermost lines and the edge of the board, relative to the line spacing.
+    #[arg(long, value_name = "MARGIN", default_value_t = 0.64)]
+    board_margin: f64,
+    /// Extra space around the drawn range in which stones and markup aren't cut off, relative
+    /// to the line spacing (at most the board margin is visible).
+    #[arg(long, value_name = "MARGIN", default_value_t = 0.0)]
+    clip_margin: f64,
+    /// Blank space around the whole diagram, labels and captions included, relative to the line
+    /// spacing. The image grows to fit it.
+    #[arg(long, value_name = "PADDING", default_value_t = 0.0)]
+    padding: f64,
+    /// Font family for all text. PNG output always uses the bundled Inter font.
+    #[arg(long, value_name = "FAMILY", default_value = "Inter")]
+    font_family: String,
+    /// Font size relative to the line spacing.
+    #[arg(long, value_name = "SIZE", default_value_t = 0.45)]
+    font_size: f64,
+    /// Font weight (e.g. 400 for normal, 700 for bold).
+    #[arg(long, value_name = "WEIGHT", default_value_t = 700)]
+    font_weight: u32,
+    /// Don't draw the board background.
+    #[arg(long)]
+    transparent_background: bool,
     /// Draw only enough of the board to hold all the stones (with 1 space padding).
     #[arg(short, long, conflicts_with = "range")]
     shrink_wrap: bool,
-    /// Range to draw as a pair of corners (e.g. 'cc-ff').
+    /// Range to draw as a pair of corners (e.g. 'cc-ff'), or 'view' to use the SGF's VW
+    /// property.
     #[arg(short, long)]
     range: Option<GobanRange>,
+    /// Draw only enough of the board to hold all the stones and markup, with the given
+    /// number of lines of padding.
+    #[arg(long, value_name = "MARGIN", conflicts_with_all = ["range", "shrink_wrap"])]
+    crop_margin: Option<u8>,
+    /// Rotate or reflect the board before drawing it. Ranges apply to the transformed board.
+    #[arg(long, value_name = "TRANSFORM", default_value = "identity")]
+    transform: BoardTransform,
     /// Style to use.
     #[arg(long = "style", value_name = "STYLE", default_value = "simple")]
     generated_style: generated_styles::GeneratedStyle,
     /// Custom style `toml` file. Conflicts with '--style'. See the README for details.
     #[arg(long, value_name = "FILE", conflicts_with = "generated_style")]
     custom_style: Option<PathBuf>,
+    /// Radius of stones relative to the line spacing (at most 0.5).
+    #[arg(long, value_name = "RADIUS", default_value_t = 0.48)]
+    stone_radius: f64,
+    /// Don't draw board lines underneath stones.
+    #[arg(long)]
+    clip_lines_under_stones: bool,
+    /// Vary the highlight of each stone slightly (deterministic by position).
+    #[arg(long)]
+    stone_variation: bool,
+    /// Opacity of stones, from 0 (invisible) to 1 (opaque).
+    #[arg(long, value_name = "OPACITY", default_value_t = 1.0)]
+    stone_opacity: f64,
+    /// Draw setup stones (AB and AW) faded so played moves stand out.
+    #[arg(long)]
+    dim_setup_stones: bool,
+    /// Draw black stones faintly.
+    #[arg(long)]
+    ghost_black: bool,
+    /// Draw white stones faintly.
+    #[arg(long)]
+    ghost_white: bool,
+    /// Comma separated points to draw hoshi on instead of the standard ones (e.g.
+    /// 'dd,jj,pp'). Useful for unusual board sizes.
+    #[arg(long = "hoshi", value_name = "POINTS", value_delimiter = ',')]
+    hoshi_points: Vec<SgfPoint>,
+    /// Thin the grid lines in proportion on boards with more than this many lines, e.g. for
+    /// 37x37 boards.
+    #[arg(
+        long = "thin-lines-above",
+        value_name = "SIZE",
+        value_parser = clap::value_parser!(u8).range(1..)
+    )]
+    line_thinning_above: Option<u8>,
+    /// Antialiasing hint for renderers (crisp-edges suits small PNGs).
+    #[arg(long, value_name = "MODE", default_value = "auto")]
+    shape_rendering: ShapeRendering,
+    /// Adjust the width slightly so stones land on whole pixels, for sharper small PNGs.
+    #[arg(long)]
+    snap_to_pixel_grid: bool,
+    /// Fill the background with a texture instead of a flat color.
+    #[arg(long, value_name = "TEXTURE")]
+    board_texture: Option<BoardTexture>,
+    /// Fade out the board past the edges of a cropped range, to show it continues.
+    #[arg(long)]
+    fade_crop_edges: bool,
+    /// Shape of the heads of arrows.
+    #[arg(long, value_name = "SHAPE", default_value = "triangle")]
+    arrowhead_style: ArrowStyle,
+    /// Shape of the ends of lines.
+    #[arg(long, value_name = "SHAPE", default_value = "dot")]
+    linehead_style: ArrowStyle,
+    /// Tilt the board away from the viewer by the given angle (0 to 90 degrees).
+    #[arg(long, value_name = "DEGREES")]
+    perspective_tilt: Option<f64>,
+    /// Direction of the light on the fancy style's stones, in degrees counterclockwise from the
+    /// right (e.g. 90 for light from above). Defaults to the top left.
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    light_angle: Option<f64>,
     /// Draw move numbers (may replace other markup).
     #[arg(long, require_equals=true, num_args = 0..=1, value_name = "RANGE", default_missing_value = "1")]
     move_numbers: Option<MoveNumberRange>,
@@ -71,9 +200,72 @@ pub struct MakeSvgArgs {
         requires = "move_numbers"
     )]
     move_numbers_from: u64,
+    /// Show move numbers relative to the first numbered move, or as absolute move numbers
+    /// (requires --move-numbers).
+    #[arg(
+        long,
+        value_name = "DISPLAY",
+        default_value = "relative",
+        requires = "move_numbers"
+    )]
+    move_number_display: MoveNumberDisplay,
+    /// Wrap displayed move numbers so they never exceed the given number (requires
+    /// --move-numbers).
+    #[arg(
+        long,
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "move_numbers"
+    )]
+    move_number_modulo: Option<u64>,
+    /// Outline move numbers on stones so they stay legible on busy boards.
+    #[arg(long)]
+    move_number_halo: bool,
+    /// Tint move numbers red for odd (Black's) moves and blue for even (White's) moves.
+    #[arg(long)]
+    parity_tint: bool,
+    /// Connect the numbered moves in order with faint lines.
+    #[arg(long = "move-trail", requires = "move_numbers")]
+    draw_move_trail: bool,
+    /// Shape drawn behind move numbers on empty points.
+    #[arg(long, value_name = "SHAPE", default_value = "rectangle")]
+    move_number_background: BackgroundShape,
+    /// Show every move number at points played more than once (e.g. '3,7') instead of just
+    /// the last one.
+    #[arg(long)]
+    show_all_numbers_at_point: bool,
+    /// Draw move numbers above all other markup instead of below it.
+    #[arg(long)]
+    move_numbers_on_top: bool,
+    /// Don't draw markup (including the last move marker) on stones, leaving only move
+    /// numbers.
+    #[arg(long)]
+    numbered_stones_only: bool,
+    /// Color for the move numbers in a range, as 'RANGE:COLOR' (e.g. '1-10:red'). May be
+    /// repeated; the first matching range is used.
+    #[arg(long = "move-number-color", value_name = "RANGE:COLOR")]
+    move_number_colors: Vec<MoveNumberColor>,
+    /// Comma separated moves to number (e.g. '3,7,12'), leaving the other stones plain.
+    #[arg(
+        long = "number-only",
+        value_name = "MOVES",
+        value_delimiter = ',',
+        requires = "move_numbers"
+    )]
+    number_only_moves: Option<Vec<u64>>,
     /// Sides to draw position labels on.
     #[arg(long, value_name = "SIDES", default_value = "nw")]
     label_sides: BoardSideSet,
+    /// Coordinate convention for position labels.
+    #[arg(long, value_name = "STYLE", default_value = "letters")]
+    coordinate_style: CoordinateStyle,
+    /// Use 'I' in lettered column labels instead of skipping it.
+    #[arg(long = "include-i-column", action = clap::ArgAction::SetFalse)]
+    skip_i_column: bool,
+    /// Label position labels as though the given point (e.g. 'ap') were A1, so a cropped
+    /// corner reads as a small board of its own.
+    #[arg(long, value_name = "POINT")]
+    label_origin: Option<SgfPoint>,
     /// Don't draw position labels.
     #[arg(long, conflicts_with = "label_sides")]
     no_board_labels: bool,
@@ -92,18 +284,153 @@ pub struct MakeSvgArgs {
     /// Don't draw SGF selected.
     #[clap(long = "no-selected", action = clap::ArgAction::SetFalse)]
     draw_selected: bool,
+    /// Comma separated kinds of markup to draw beneath the stones (e.g. 'square,selected').
+    #[arg(long, value_name = "KINDS", value_delimiter = ',')]
+    background_markup: Vec<MarkupKind>,
     /// Don't draw SGF dimmed.
     #[clap(long = "no-dimmed", action = clap::ArgAction::SetFalse)]
     draw_dimmed: bool,
     /// Don't draw SGF labels.
     #[clap(long = "no-labels", action = clap::ArgAction::SetFalse)]
     draw_labels: bool,
+    /// Characters of each SGF label to draw. Labels longer than 2 characters are shrunk to fit.
+    #[arg(
+        long,
+        value_name = "NUM",
+        default_value_t = 2,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_label_chars: usize,
+    /// End labels cut short by --max-label-chars with an ellipsis.
+    #[arg(long)]
+    label_ellipsis: bool,
+    /// Draw each distinct SGF label text in its own color, the same in every diagram.
+    #[arg(long)]
+    colorize_labels: bool,
     /// Don't draw SGF lines.
     #[clap(long = "no-lines", action = clap::ArgAction::SetFalse)]
     draw_lines: bool,
     /// Don't draw SGF arrows.
     #[clap(long = "no-arrows", action = clap::ArgAction::SetFalse)]
     draw_arrows: bool,
+    /// Comma separated dash pattern for SGF lines and arrows, in board cells (e.g. '0.2,0.1').
+    #[arg(long, value_name = "DASHES", value_delimiter = ',')]
+    line_dash: Vec<f64>,
+    /// Leave out SGF lines and arrows which leave the drawn range, instead of cutting them off.
+    #[arg(long)]
+    trim_offboard_annotations: bool,
+    /// Draw SGF territory (TB and TW).
+    #[clap(long = "territory")]
+    draw_territory: bool,
+    /// Mark every empty point with a faint dot.
+    #[arg(long = "all-points")]
+    draw_all_points: bool,
+    /// Don't draw the board's grid lines.
+    #[arg(long = "no-grid-lines", action = clap::ArgAction::SetFalse)]
+    draw_grid_lines: bool,
+    /// Don't draw hoshi (star points).
+    #[arg(long = "no-hoshi", action = clap::ArgAction::SetFalse)]
+    draw_hoshi: bool,
+    /// Add CSS classes to stones, board lines and markup instead of inline colors, for styling
+    /// with a stylesheet.
+    #[arg(long)]
+    css_classes: bool,
+    /// Move repeated colors and stroke widths into a stylesheet in the SVG instead of repeating
+    /// them on each element, for smaller output.
+    #[arg(long = "no-inline-styles", action = clap::ArgAction::SetFalse)]
+    inline_styles: bool,
+    /// Round coordinates in the SVG to this many decimal places, for smaller output. Sizes,
+    /// opacities and the diagram's scale aren't rounded.
+    #[arg(long, value_name = "DIGITS")]
+    coordinate_precision: Option<u8>,
+    /// Give each intersection a title with its coordinate, shown as a tooltip by browsers.
+    #[arg(long)]
+    embed_point_titles: bool,
+    /// Give each move's stone and number an id and a data-move attribute, for revealing moves
+    /// one at a time in presentations.
+    #[arg(long)]
+    layer_moves: bool,
+    /// Mark the stone played at the selected node.
+    #[clap(long = "last-move")]
+    draw_last_move: bool,
+    /// Comma separated stones to annotate with the liberty count of their group (e.g. 'cc,dd').
+    #[arg(long = "liberties", value_name = "POINTS", value_delimiter = ',')]
+    annotate_liberties: Vec<SgfPoint>,
+    /// Comma separated points to circle with a ring (e.g. 'cc,dd'), whether or not they're
+    /// marked in the SGF.
+    #[arg(long = "highlight", value_name = "POINTS", value_delimiter = ',')]
+    highlight_moves: Vec<SgfPoint>,
+    /// Comma separated stones whose groups to outline (e.g. 'cc,dd').
+    #[arg(long = "outline-groups", value_name = "POINTS", value_delimiter = ',')]
+    outline_groups: Vec<SgfPoint>,
+    /// Comma separated dead stones to fade (e.g. 'cc,dd'), for scoring diagrams. Combine with
+    /// --territory to mark territory over them.
+    #[arg(long = "dead-stones", value_name = "POINTS", value_delimiter = ',')]
+    dead_stones: Vec<SgfPoint>,
+    /// Fill for the stone at a point, as 'POINT:COLOR' (e.g. 'cc:#c33'), instead of the
+    /// style's. May be repeated.
+    #[arg(long = "stone-fill", value_name = "POINT:COLOR")]
+    stone_fills: Vec<StoneFill>,
+    /// Comma separated points to mark with a green check as correct answers (e.g. 'cc,dd').
+    #[arg(long = "correct", value_name = "POINTS", value_delimiter = ',')]
+    correct_points: Vec<SgfPoint>,
+    /// Comma separated points to mark with a red cross as wrong answers (e.g. 'cc,dd').
+    #[arg(long = "wrong", value_name = "POINTS", value_delimiter = ',')]
+    wrong_points: Vec<SgfPoint>,
+    /// Mark the point where an immediate recapture is forbidden by the ko rule.
+    #[clap(long = "ko")]
+    draw_ko: bool,
+    /// Mark the points where stones were captured by the selected move with an X.
+    #[clap(long = "capture-markers")]
+    draw_capture_markers: bool,
+    /// Draw the selected node's comment below the diagram.
+    #[clap(long = "show-comment")]
+    draw_comment: bool,
+    /// List the comments on numbered moves in a column beside the board.
+    #[arg(long = "comment-list")]
+    draw_comment_list: bool,
+    /// List the passes played so far below the diagram.
+    #[clap(long = "show-passes")]
+    draw_passes: bool,
+    /// Draw a key below the diagram for the kinds of markup shown.
+    #[arg(long = "legend")]
+    draw_legend: bool,
+    /// Legend text for a kind of markup, as 'KIND:TEXT' (e.g. 'triangle:Key stones'). May be
+    /// repeated. Kinds are mark, triangle, circle, square and selected.
+    #[arg(
+        long = "legend-label",
+        value_name = "KIND:TEXT",
+        requires = "draw_legend"
+    )]
+    legend_labels: Vec<LegendLabel>,
+    /// Draw a header with the players and result above the diagram.
+    #[clap(long = "show-title")]
+    draw_title: bool,
+    /// Draw the number of stones captured by each player above the diagram.
+    #[clap(long = "show-captures")]
+    draw_captures: bool,
+    /// Add a title and description for screen readers, generating the description unless
+    /// '--description' is given.
+    #[arg(long)]
+    accessible: bool,
+    /// Description of the diagram for screen readers (implies --accessible).
+    #[arg(long, value_name = "TEXT")]
+    description: Option<String>,
+    /// Stamp the game result (RE) on the board when rendering the end of the game.
+    #[arg(long = "result-stamp")]
+    draw_result_stamp: bool,
+    /// Corner of the board for the result stamp.
+    #[arg(long, value_name = "CORNER", default_value = "bottom-right")]
+    result_stamp_corner: BoardCorner,
+    /// Draw a box with the komi (KM), handicap (HA), ruleset (RU) and board size on the board.
+    #[arg(long = "info-box")]
+    draw_info_box: bool,
+    /// Corner of the board for the info box.
+    #[arg(long, value_name = "CORNER", default_value = "top-left")]
+    info_box_corner: BoardCorner,
+    /// How to show the game result in the title and result stamp.
+    #[arg(long, value_name = "FORMAT", default_value = "raw")]
+    result_format: ResultFormat,
     /// Don't draw any markup on points.
     #[clap(long)]
     no_point_markup: bool,
@@ -122,6 +449,8 @@ impl MakeSvgArgs {
 
         let goban_range = if self.shrink_wrap {
             GobanRange::ShrinkWrap
+        } else if let Some(margin) = self.crop_margin {
+            GobanRange::Shrink { margin }
         } else if let Some(range) = &self.range {
             range.clone()
         } else {
@@ -137,18 +466,64 @@ impl MakeSvgArgs {
             None => self.generated_style.style().clone(),
         };
 
+        if !(0.0..=1.0).contains(&self.stone_opacity) {
+            return Err(UsageError::InvalidStoneOpacity);
+        }
+
+        if !(self.stone_radius > 0.0 && self.stone_radius <= 0.5) {
+            return Err(UsageError::InvalidStoneRadius);
+        }
+
+        if self.board_margin < 0.0 {
+            return Err(UsageError::InvalidBoardMargin);
+        }
+
+        if self.clip_margin < 0.0 {
+            return Err(UsageError::InvalidClipMargin);
+        }
+
+        if self.padding < 0.0 {
+            return Err(UsageError::InvalidPadding);
+        }
+
+        if self.line_dash.iter().any(|&dash| dash < 0.0) {
+            return Err(UsageError::InvalidLineDash);
+        }
+
+        if let Some(height) = self.viewbox_height {
+            if height <= 0.0 {
+                return Err(UsageError::InvalidHeight);
+            }
+        }
+
+        if let Some(cell_size) = self.cell_size {
+            if cell_size <= 0.0 {
+                return Err(UsageError::InvalidCellSize);
+            }
+        }
+
+        if let Some(tilt) = self.perspective_tilt {
+            if !(0.0..90.0).contains(&tilt) {
+                return Err(UsageError::InvalidPerspectiveTilt);
+            }
+        }
+
         let count_from = self.move_numbers_from;
         let move_number_options = if let Some(range) = self.move_numbers {
             Some(MoveNumberOptions {
                 start: range.start,
                 end: range.end,
                 count_from,
+                display: self.move_number_display,
+                modulo: self.move_number_modulo,
             })
         } else if self.kifu {
             Some(MoveNumberOptions {
                 start: 1,
                 end: None,
                 count_from,
+                display: self.move_number_display,
+                modulo: self.move_number_modulo,
             })
         } else {
             None
@@ -163,30 +538,156 @@ impl MakeSvgArgs {
 
         Ok(MakeSvgOptions {
             node_description,
+            game_index: self.game_index,
+            strict: self.strict,
             goban_range,
+            transform: self.transform,
+            rotate_quarter_turns: 0,
             style,
-            viewbox_width: self.viewbox_width,
+            size: self
+                .cell_size
+                .map_or(SizeSpec::TotalWidth(self.viewbox_width), SizeSpec::CellSize),
+            simplify_below_width: self.simplify_below_width,
+            viewbox_height: self.viewbox_height,
+            board_margin: self.board_margin,
+            clip_margin: self.clip_margin,
+            padding: self.padding,
+            font_family: self.font_family.clone(),
+            font_size: self.font_size,
+            font_weight: self.font_weight,
+            transparent_background: self.transparent_background,
+            stone_radius: self.stone_radius,
+            stone_variation: self.stone_variation,
+            clip_lines_under_stones: self.clip_lines_under_stones,
+            stone_opacity: self.stone_opacity,
+            dim_setup_stones: self.dim_setup_stones,
+            ghost_black: self.ghost_black,
+            ghost_white: self.ghost_white,
+            perspective_tilt: self.perspective_tilt,
+            light_angle: self.light_angle,
+            shape_rendering: self.shape_rendering,
+            snap_to_pixel_grid: self.snap_to_pixel_grid,
+            board_texture: self.board_texture,
+            fade_crop_edges: self.fade_crop_edges,
+            arrowhead_style: self.arrowhead_style,
+            linehead_style: self.linehead_style,
+            hoshi_points: self.hoshi_points.iter().map(|p| (p.0, p.1)).collect(),
+            line_thinning_above: self.line_thinning_above,
             label_sides,
+            coordinate_style: self.coordinate_style,
+            skip_i_column: self.skip_i_column,
+            label_origin: self.label_origin.map(|p| (p.0, p.1)),
             move_number_options,
+            numbered_stones_only: self.numbered_stones_only,
+            reset_numbering_per_segment: false,
+            move_number_halo: self.move_number_halo,
+            parity_tint: self.parity_tint,
+            draw_move_trail: self.draw_move_trail,
+            move_number_background: self.move_number_background,
+            move_numbers_on_top: self.move_numbers_on_top,
+            show_all_numbers_at_point: self.show_all_numbers_at_point,
+            number_only_moves: self.number_only_moves.clone(),
+            move_number_color_ranges: self
+                .move_number_colors
+                .iter()
+                .map(|c| {
+                    (
+                        c.range.start..c.range.end.map_or(u64::MAX, |end| end.saturating_add(1)),
+                        c.color.clone(),
+                    )
+                })
+                .collect(),
             draw_marks: self.draw_marks && !no_point_markup,
             draw_triangles: self.draw_triangles && !no_point_markup,
             draw_circles: self.draw_circles && !no_point_markup,
             draw_squares: self.draw_squares && !no_point_markup,
             draw_selected: self.draw_selected && !no_point_markup,
+            background_markup: self.background_markup.clone(),
             draw_dimmed: self.draw_dimmed && !no_point_markup,
             draw_labels: self.draw_labels && !no_point_markup,
+            max_label_chars: self.max_label_chars,
+            label_ellipsis: self.label_ellipsis,
+            colorize_labels: self.colorize_labels,
             draw_lines: self.draw_lines && !no_point_markup,
             draw_arrows: self.draw_arrows && !no_point_markup,
+            line_dash: (!self.line_dash.is_empty()).then(|| self.line_dash.clone()),
+            trim_offboard_annotations: self.trim_offboard_annotations,
+            draw_territory: self.draw_territory,
+            draw_all_points: self.draw_all_points,
+            draw_grid_lines: self.draw_grid_lines,
+            draw_hoshi: self.draw_hoshi,
+            embed_point_titles: self.embed_point_titles,
+            layer_moves: self.layer_moves,
+            emit_css_classes: self.css_classes,
+            inline_styles: self.inline_styles,
+            coordinate_precision: self.coordinate_precision,
+            annotate_liberties: self.annotate_liberties.iter().map(|p| (p.0, p.1)).collect(),
+            highlight_moves: self.highlight_moves.iter().map(|p| (p.0, p.1)).collect(),
+            outline_groups: self.outline_groups.iter().map(|p| (p.0, p.1)).collect(),
+            dead_stones: self.dead_stones.iter().map(|p| (p.0, p.1)).collect(),
+            stone_overrides: self
+                .stone_fills
+                .iter()
+                .map(|fill| ((fill.point.0, fill.point.1), fill.color.clone()))
+                .collect(),
+            solution_marks: self
+                .correct_points
+                .iter()
+                .map(|p| ((p.0, p.1), SolutionKind::Correct))
+                .chain(
+                    self.wrong_points
+                        .iter()
+                        .map(|p| ((p.0, p.1), SolutionKind::Wrong)),
+                )
+                .collect(),
+            influence: HashMap::new(),
+            influence_colors: ("black".to_string(), "white".to_string()),
+            draw_last_move: self.draw_last_move,
+            draw_ko: self.draw_ko,
+            draw_capture_markers: self.draw_capture_markers,
+            draw_comment: self.draw_comment,
+            draw_comment_list: self.draw_comment_list,
+            draw_passes: self.draw_passes,
+            draw_legend: self.draw_legend,
+            legend_labels: self
+                .legend_labels
+                .iter()
+                .map(|label| (label.kind, label.text.clone()))
+                .collect(),
+            draw_title: self.draw_title,
+            draw_captures: self.draw_captures,
+            draw_result_stamp: self.draw_result_stamp,
+            result_stamp_corner: self.result_stamp_corner,
+            draw_info_box: self.draw_info_box,
+            info_box_corner: self.info_box_corner,
+            result_format: self.result_format,
+            accessible_description: self.description.clone(),
+            generate_a11y: self.accessible,
             kifu_mode: self.kifu,
         })
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    Sgf,
+    Gib,
+}
+
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 pub enum OutputFormat {
     Svg,
+    Ascii,
     #[cfg(feature = "png")]
     Png,
+    #[cfg(feature = "gif")]
+    Gif,
+    #[cfg(feature = "pdf")]
+    Pdf,
+    #[cfg(feature = "webp")]
+    Webp,
+    #[cfg(feature = "json")]
+    Json,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -203,6 +704,9 @@ impl std::str::FromStr for MoveNumberRange {
         let start = parts[0]
             .parse()
             .map_err(|_| UsageError::InvalidFirstMoveNumber)?;
+        if start == 0 {
+            return Err(UsageError::InvalidFirstMoveNumber);
+        }
         let end = parts
             .get(1)
             .map(|end| end.parse())
@@ -211,3 +715,86 @@ impl std::str::FromStr for MoveNumberRange {
         Ok(MoveNumberRange { start, end })
     }
 }
+
+#[derive(Debug, Clone)]
+struct MoveNumberColor {
+    range: MoveNumberRange,
+    color: String,
+}
+
+impl std::str::FromStr for MoveNumberColor {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (range, color) = s
+            .split_once(':')
+            .ok_or(UsageError::InvalidMoveNumberColor)?;
+        if color.is_empty() {
+            return Err(UsageError::InvalidMoveNumberColor);
+        }
+        Ok(MoveNumberColor {
+            range: range.parse()?,
+            color: color.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LegendLabel {
+    kind: MarkupKind,
+    text: String,
+}
+
+impl std::str::FromStr for LegendLabel {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, text) = s.split_once(':').ok_or(UsageError::InvalidLegendLabel)?;
+        let kind = <MarkupKind as clap::ValueEnum>::from_str(kind, true)
+            .map_err(|_| UsageError::InvalidLegendLabel)?;
+        Ok(LegendLabel {
+            kind,
+            text: text.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoneFill {
+    point: SgfPoint,
+    color: String,
+}
+
+impl std::str::FromStr for StoneFill {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (point, color) = s.split_once(':').ok_or(UsageError::InvalidStoneFill)?;
+        if color.is_empty() {
+            return Err(UsageError::InvalidStoneFill);
+        }
+        Ok(StoneFill {
+            point: point.parse()?,
+            color: color.to_string(),
+        })
+    }
+}
+
+/// A point in SGF notation, e.g. 'dd'.
+#[derive(Debug, Clone, Copy)]
+struct SgfPoint(u8, u8);
+
+impl std::str::FromStr for SgfPoint {
+    type Err = UsageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_byte = |b: u8| match b {
+            b'a'..=b'z' => Ok(b - b'a'),
+            _ => Err(UsageError::InvalidPoint),
+        };
+        match s.as_bytes() {
+            &[x, y] => Ok(SgfPoint(parse_byte(x)?, parse_byte(y)?)),
+            _ => Err(UsageError::InvalidPoint),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+/// One of the 8 symmetries of the board.
+///
+/// Rotations are clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, clap::ValueEnum)]
+pub enum BoardTransform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    /// Reflect across the diagonal through the top left corner.
+    Transpose,
+    /// Reflect across the diagonal through the top right corner.
+    AntiTranspose,
+}
+
+impl BoardTransform {
+    const ALL: [BoardTransform; 8] = [
+        BoardTransform::Identity,
+        BoardTransform::Rotate90,
+        BoardTransform::Rotate180,
+        BoardTransform::Rotate270,
+        BoardTransform::FlipHorizontal,
+        BoardTransform::FlipVertical,
+        BoardTransform::Transpose,
+        BoardTransform::AntiTranspose,
+    ];
+
+    /// The clockwise rotation by the given number of quarter turns.
+    pub fn quarter_turns(turns: u8) -> Self {
+        match turns % 4 {
+            0 => BoardTransform::Identity,
+            1 => BoardTransform::Rotate90,
+            2 => BoardTransform::Rotate180,
+            _ => BoardTransform::Rotate270,
+        }
+    }
+
+    /// The transform which applies `self` and then `other`.
+    pub fn then(self, other: BoardTransform) -> Self {
+        // A symmetry is determined by where it sends a corner and its two neighbors, on a board
+        // with unequal sides so rotations and reflections can't be confused.
+        let size = (3, 4);
+        let points = [(0, 0), (1, 0), (0, 1)];
+        let composed = |point| other.apply(self.apply(point, size), self.apply_to_size(size));
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|candidate| {
+                points
+                    .iter()
+                    .all(|&p| candidate.apply(p, size) == composed(p))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Map a point on a board of the given size to its transformed location.
+    pub fn apply(&self, point: (u8, u8), size: (u8, u8)) -> (u8, u8) {
+        let (x, y) = point;
+        let (width, height) = size;
+        match self {
+            BoardTransform::Identity => (x, y),
+            BoardTransform::Rotate90 => (height - 1 - y, x),
+            BoardTransform::Rotate180 => (width - 1 - x, height - 1 - y),
+            BoardTransform::Rotate270 => (y, width - 1 - x),
+            BoardTransform::FlipHorizontal => (width - 1 - x, y),
+            BoardTransform::FlipVertical => (x, height - 1 - y),
+            BoardTransform::Transpose => (y, x),
+            BoardTransform::AntiTranspose => (height - 1 - y, width - 1 - x),
+        }
+    }
+
+    /// The size of the transformed board.
+    pub fn apply_to_size(&self, size: (u8, u8)) -> (u8, u8) {
+        match self {
+            BoardTransform::Identity
+            | BoardTransform::Rotate180
+            | BoardTransform::FlipHorizontal
+            | BoardTransform::FlipVertical => size,
+            BoardTransform::Rotate90
+            | BoardTransform::Rotate270
+            | BoardTransform::Transpose
+            | BoardTransform::AntiTranspose => (size.1, size.0),
+        }
+    }
+}
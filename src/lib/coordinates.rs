@@ -0,0 +1,92 @@
+/// Letters used for column labels. 'I' is skipped to avoid confusion with 'J'.
+static COLUMN_LETTERS: [char; 25] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T',
+    'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+/// Letters used for column labels when 'I' isn't skipped.
+static ALL_COLUMN_LETTERS: [char; 26] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+/// Convention for labelling board coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum CoordinateStyle {
+    /// Lettered columns (skipping 'I') and rows numbered from the bottom (e.g. "Q16").
+    Letters,
+    /// Numbered columns and rows numbered from the bottom (e.g. "16-16").
+    Numeric,
+    /// Columns and rows both numbered from the top left corner (e.g. "16-4").
+    NumericBoth,
+}
+
+impl CoordinateStyle {
+    /// Label for column `x`. With `skip_i`, lettered columns go from 'H' straight to 'J'.
+    pub fn column_label(&self, x: u8, skip_i: bool) -> String {
+        match self {
+            Self::Letters => {
+                let letters: &[char] = if skip_i {
+                    &COLUMN_LETTERS
+                } else {
+                    &ALL_COLUMN_LETTERS
+                };
+                // Columns past 'Z' continue with 'AA', 'AB', etc.
+                let x = usize::from(x);
+                if x < letters.len() {
+                    letters[x].to_string()
+                } else {
+                    let first = letters[x / letters.len() - 1];
+                    let second = letters[x % letters.len()];
+                    format!("{}{}", first, second)
+                }
+            }
+            Self::Numeric | Self::NumericBoth => (u16::from(x) + 1).to_string(),
+        }
+    }
+
+    /// Label for row `y` on a board with `height` rows.
+    pub fn row_label(&self, y: u8, height: u8) -> String {
+        match self {
+            Self::Letters | Self::Numeric => (height - y).to_string(),
+            Self::NumericBoth => (u16::from(y) + 1).to_string(),
+        }
+    }
+
+    /// Label for column `x` when column `origin_x` is labelled as the first, or `None` if `x`
+    /// comes before it.
+    pub fn column_label_from(&self, x: u8, origin_x: u8, skip_i: bool) -> Option<String> {
+        x.checked_sub(origin_x)
+            .map(|x| self.column_label(x, skip_i))
+    }
+
+    /// Label for row `y` when row `origin_y` is labelled as the first, or `None` if `y` is on
+    /// the wrong side of it. The first row is at the bottom unless rows are numbered from the
+    /// top.
+    pub fn row_label_from(&self, y: u8, origin_y: u8) -> Option<String> {
+        match self {
+            Self::Letters | Self::Numeric => {
+                (y <= origin_y).then(|| (u16::from(origin_y - y) + 1).to_string())
+            }
+            Self::NumericBoth => y.checked_sub(origin_y).map(|y| self.row_label(y, 0)),
+        }
+    }
+
+    /// Label for the point `(x, y)` on a board of the given size.
+    pub fn point_label(&self, x: u8, y: u8, goban_size: (u8, u8), skip_i: bool) -> String {
+        let column = self.column_label(x, skip_i);
+        let row = self.row_label(y, goban_size.1);
+        match self {
+            Self::Letters => format!("{}{}", column, row),
+            Self::Numeric | Self::NumericBoth => format!("{}-{}", column, row),
+        }
+    }
+
+    /// The largest number of columns which can be labelled.
+    pub fn max_columns(&self) -> u8 {
+        match self {
+            Self::Letters => u8::MAX,
+            Self::Numeric | Self::NumericBoth => 99,
+        }
+    }
+}
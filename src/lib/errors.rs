@@ -1,35 +1,115 @@
 use sgf_parse::SgfParseError;
 
+use crate::parse::ParseErrorLocation;
+
 #[derive(Debug)]
 pub enum MakeSvgError {
-    ParseError(SgfParseError),
+    ParseError(SgfParseError, Option<ParseErrorLocation>),
     StyleDefError(minidom::Error),
     InsufficientSgfNodes,
     MissingVariation,
+    MissingGame,
+    NodeOutOfRange(usize, usize),
     InvalidMoveError,
+    PointOffBoard((u8, u8)),
     InvalidRange,
     UnlabellableRange,
+    InvalidOptions(String),
+    WriteError(minidom::Error),
+    GibParseError(String),
+    ReadError(std::io::Error),
+    FileError(std::path::PathBuf, std::io::Error),
+    InvalidUtf8(std::string::FromUtf8Error),
+    #[cfg(feature = "png")]
+    PngError(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "gif")]
+    GifError(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "pdf")]
+    PdfError(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "webp")]
+    WebpError(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "json")]
+    JsonError(Box<dyn std::error::Error + Send + Sync>),
 }
 
+/// Variants wrapping another error describe only what failed, and return the underlying error
+/// from `source()`, so reporters walking the chain show each cause once.
 impl std::fmt::Display for MakeSvgError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::ParseError(e) => write!(f, "{}", e),
-            Self::StyleDefError(e) => write!(f, "Invalid defs in style: {}", e),
-            Self::InvalidMoveError => write!(f, "Invalid move"),
-            Self::InsufficientSgfNodes => write!(f, "Insufficient SGF nodes found"),
+            Self::ParseError(_, None) => write!(f, "Invalid SGF"),
+            Self::ParseError(_, Some(location)) => write!(f, "Invalid SGF at {}", location),
+            Self::StyleDefError(_) => write!(f, "Invalid defs in style"),
+            Self::InvalidMoveError => {
+                write!(f, "Invalid move: the point is already occupied.")
+            }
+            Self::PointOffBoard((x, y)) => {
+                write!(f, "Point ({}, {}) is outside the board.", x, y)
+            }
+            Self::InsufficientSgfNodes => write!(f, "No SGF nodes found to render."),
             Self::MissingVariation => write!(f, "Selected variation not found."),
+            Self::MissingGame => write!(f, "Selected game not found in collection."),
+            Self::NodeOutOfRange(requested, available) => write!(
+                f,
+                "Can't advance {} nodes; only {} follow in this variation.",
+                requested, available
+            ),
             Self::InvalidRange => write!(f, "Invalid range to render in goban."),
             Self::UnlabellableRange => write!(f, "Range too large for use with labels."),
+            Self::InvalidOptions(reason) => write!(f, "Invalid options: {}", reason),
+            Self::WriteError(_) => write!(f, "Failed to write svg"),
+            Self::GibParseError(line) => write!(f, "Invalid GIB line: {}", line),
+            Self::ReadError(_) => write!(f, "Failed to read input"),
+            Self::FileError(path, _) => write!(f, "Failed to write {}", path.display()),
+            Self::InvalidUtf8(_) => write!(f, "Input is not valid UTF-8"),
+            #[cfg(feature = "png")]
+            Self::PngError(_) => write!(f, "Failed to render PNG"),
+            #[cfg(feature = "gif")]
+            Self::GifError(_) => write!(f, "Failed to render GIF"),
+            #[cfg(feature = "pdf")]
+            Self::PdfError(_) => write!(f, "Failed to render PDF"),
+            #[cfg(feature = "webp")]
+            Self::WebpError(_) => write!(f, "Failed to render WebP"),
+            #[cfg(feature = "json")]
+            Self::JsonError(_) => write!(f, "Failed to serialize JSON"),
         }
     }
 }
 
-impl std::error::Error for MakeSvgError {}
+impl std::error::Error for MakeSvgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ParseError(e, _) => Some(e),
+            Self::StyleDefError(e) | Self::WriteError(e) => Some(e),
+            Self::ReadError(e) | Self::FileError(_, e) => Some(e),
+            Self::InvalidUtf8(e) => Some(e),
+            #[cfg(feature = "png")]
+            Self::PngError(e) => Some(e.as_ref()),
+            #[cfg(feature = "gif")]
+            Self::GifError(e) => Some(e.as_ref()),
+            #[cfg(feature = "pdf")]
+            Self::PdfError(e) => Some(e.as_ref()),
+            #[cfg(feature = "webp")]
+            Self::WebpError(e) => Some(e.as_ref()),
+            #[cfg(feature = "json")]
+            Self::JsonError(e) => Some(e.as_ref()),
+            Self::InsufficientSgfNodes
+            | Self::MissingVariation
+            | Self::MissingGame
+            | Self::NodeOutOfRange(_, _)
+            | Self::InvalidMoveError
+            | Self::PointOffBoard(_)
+            | Self::InvalidRange
+            | Self::UnlabellableRange
+            | Self::InvalidOptions(_)
+            | Self::GibParseError(_) => None,
+        }
+    }
+}
 
 impl From<SgfParseError> for MakeSvgError {
     fn from(error: SgfParseError) -> Self {
-        Self::ParseError(error)
+        Self::ParseError(error, None)
     }
 }
 
@@ -40,20 +120,57 @@ pub enum UsageError {
     InvalidFirstMoveNumber,
     InvalidLastMoveNumber,
     InvalidBoardSides,
+    InvalidStoneOpacity,
+    InvalidStoneRadius,
+    InvalidPerspectiveTilt,
+    InvalidHeight,
+    InvalidCellSize,
+    InvalidBoardMargin,
+    InvalidClipMargin,
+    InvalidPadding,
+    InvalidLineDash,
+    InvalidMoveNumberColor,
+    InvalidLegendLabel,
+    InvalidPoint,
+    InvalidStoneFill,
 }
 
 impl std::fmt::Display for UsageError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             UsageError::InvalidRange => write!(f, "Invalid range."),
-            UsageError::StyleReadError(e) => write!(f, "Failed to read style file: {}", e),
+            UsageError::StyleReadError(_) => write!(f, "Failed to read style file"),
             UsageError::InvalidFirstMoveNumber => write!(f, "Invalid first move number."),
             UsageError::InvalidLastMoveNumber => write!(f, "Invalid last move number."),
             UsageError::InvalidBoardSides => write!(f, "Invalid board sides."),
+            UsageError::InvalidStoneOpacity => write!(f, "Stone opacity must be between 0 and 1."),
+            UsageError::InvalidStoneRadius => {
+                write!(f, "Stone radius must be greater than 0 and at most 0.5.")
+            }
+            UsageError::InvalidPerspectiveTilt => {
+                write!(f, "Perspective tilt must be between 0 and 90 degrees.")
+            }
+            UsageError::InvalidHeight => write!(f, "Height must be positive."),
+            UsageError::InvalidCellSize => write!(f, "Cell size must be positive."),
+            UsageError::InvalidBoardMargin => write!(f, "Board margin must not be negative."),
+            UsageError::InvalidClipMargin => write!(f, "Clip margin must not be negative."),
+            UsageError::InvalidPadding => write!(f, "Padding must not be negative."),
+            UsageError::InvalidLineDash => write!(f, "Line dashes must not be negative."),
+            UsageError::InvalidMoveNumberColor => write!(f, "Invalid move number color."),
+            UsageError::InvalidLegendLabel => write!(f, "Invalid legend label."),
+            UsageError::InvalidPoint => write!(f, "Invalid point."),
+            UsageError::InvalidStoneFill => write!(f, "Invalid stone fill."),
         }
     }
 }
 
-impl std::error::Error for UsageError {}
+impl std::error::Error for UsageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UsageError::StyleReadError(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
 unsafe impl Send for UsageError {}
 unsafe impl Sync for UsageError {}
@@ -0,0 +1,139 @@
+use sgf_parse::{go, SgfNode};
+
+use crate::errors::MakeSvgError;
+use crate::goban::get_board_size;
+use crate::parse::parse_go;
+
+/// Game information read from the root node of a collection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameInfo {
+    pub black_player: Option<String>,
+    pub black_rank: Option<String>,
+    pub white_player: Option<String>,
+    pub white_rank: Option<String>,
+    pub komi: Option<f64>,
+    pub handicap: Option<i64>,
+    pub ruleset: Option<String>,
+    pub result: Option<String>,
+    pub date: Option<String>,
+    pub event: Option<String>,
+    pub board_size: (u8, u8),
+}
+
+/// Read the game information of the first game in `sgf`.
+///
+/// Only the root node is inspected, so this is cheap even for long games.
+pub fn game_info(sgf: &str) -> Result<GameInfo, MakeSvgError> {
+    let collection = parse_go(sgf)?;
+    let root = collection
+        .first()
+        .ok_or(MakeSvgError::InsufficientSgfNodes)?;
+    Ok(GameInfo::from_root(root))
+}
+
+impl GameInfo {
+    pub fn from_root(sgf_node: &SgfNode<go::Prop>) -> Self {
+        let mut game_info = GameInfo {
+            board_size: get_board_size(sgf_node),
+            ..Default::default()
+        };
+        for prop in sgf_node.properties() {
+            match prop {
+                go::Prop::PB(text) => game_info.black_player = Some(text.to_string()),
+                go::Prop::BR(text) => game_info.black_rank = Some(text.to_string()),
+                go::Prop::PW(text) => game_info.white_player = Some(text.to_string()),
+                go::Prop::WR(text) => game_info.white_rank = Some(text.to_string()),
+                go::Prop::KM(komi) => game_info.komi = Some(*komi),
+                go::Prop::HA(handicap) => game_info.handicap = Some(*handicap),
+                go::Prop::RU(text) => game_info.ruleset = Some(text.to_string()),
+                go::Prop::RE(text) => game_info.result = Some(text.to_string()),
+                go::Prop::DT(text) => game_info.date = Some(text.to_string()),
+                go::Prop::EV(text) => game_info.event = Some(text.to_string()),
+                _ => {}
+            }
+        }
+        game_info
+    }
+
+    /// A header line like "White (5d) vs Black (4d) — W+Resign".
+    ///
+    /// Missing properties are left out. Returns `None` if there's nothing to show.
+    pub fn title(&self) -> Option<String> {
+        self.formatted_title(ResultFormat::Raw)
+    }
+
+    /// The header line from `title`, with the result shown in `result_format`.
+    pub fn formatted_title(&self, result_format: ResultFormat) -> Option<String> {
+        let players: Vec<String> = [
+            (&self.white_player, &self.white_rank),
+            (&self.black_player, &self.black_rank),
+        ]
+        .iter()
+        .filter_map(|(player, rank)| {
+            let player = player.as_deref().filter(|s| !s.is_empty())?;
+            Some(match rank.as_deref().filter(|s| !s.is_empty()) {
+                Some(rank) => format!("{} ({})", player, rank),
+                None => player.to_string(),
+            })
+        })
+        .collect();
+        let mut pieces = vec![];
+        if !players.is_empty() {
+            pieces.push(players.join(" vs "));
+        }
+        if let Some(result) = self.result.as_deref().filter(|s| !s.is_empty()) {
+            pieces.push(result_format.format(result));
+        }
+        if pieces.is_empty() {
+            None
+        } else {
+            Some(pieces.join(" — "))
+        }
+    }
+}
+
+/// How to show a game result (`RE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResultFormat {
+    /// As written in the SGF, e.g. "W+R".
+    Raw,
+    /// Spelled out, e.g. "White wins by resignation".
+    Verbose,
+}
+
+impl ResultFormat {
+    /// Format `result`. Results which don't follow the SGF spec are left as they are.
+    pub fn format(self, result: &str) -> String {
+        match self {
+            ResultFormat::Raw => result.to_string(),
+            ResultFormat::Verbose => verbose_result(result).unwrap_or_else(|| result.to_string()),
+        }
+    }
+}
+
+fn verbose_result(result: &str) -> Option<String> {
+    match result {
+        "0" | "Draw" => return Some("Draw".to_string()),
+        "Void" => return Some("No result".to_string()),
+        "?" => return Some("Unknown result".to_string()),
+        _ => {}
+    }
+    let (winner, margin) = result.split_once('+')?;
+    let winner = match winner {
+        "B" => "Black",
+        "W" => "White",
+        _ => return None,
+    };
+    let how = match margin {
+        "" => return Some(format!("{} wins", winner)),
+        "R" | "Resign" => "by resignation".to_string(),
+        "T" | "Time" => "on time".to_string(),
+        "F" | "Forfeit" => "by forfeit".to_string(),
+        _ => {
+            let points: f64 = margin.parse().ok()?;
+            let unit = if points == 1.0 { "point" } else { "points" };
+            format!("by {} {}", margin, unit)
+        }
+    };
+    Some(format!("{} wins {}", winner, how))
+}
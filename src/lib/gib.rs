@@ -0,0 +1,91 @@
+use crate::errors::MakeSvgError;
+use crate::handicap::handicap_points;
+
+/// Convert a Tygem GIB game record to SGF.
+///
+/// Reads the player names, handicap (`INI` line) and moves (`STO` and `SKI` lines). GIB
+/// records are always for 19x19 games.
+pub fn gib_to_sgf(gib: &str) -> Result<String, MakeSvgError> {
+    let mut root = String::from(";GM[1]FF[4]SZ[19]");
+    let mut moves = String::new();
+    let mut next_color = "B";
+    for line in gib.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix("\\[").and_then(|s| s.strip_suffix("\\]")) {
+            if let Some((key, value)) = header.split_once('=') {
+                let property = match key {
+                    "GAMEBLACKNAME" => "PB",
+                    "GAMEWHITENAME" => "PW",
+                    _ => continue,
+                };
+                root.push_str(&format!("{}[{}]", property, escape(value.trim())));
+            }
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first() {
+            Some(&"INI") => {
+                let handicap = field(&fields, 3)?;
+                if handicap >= 2 {
+                    let points = handicap_points((19, 19), handicap);
+                    if points.is_empty() {
+                        return Err(invalid(line));
+                    }
+                    root.push_str(&format!("HA[{}]AB", handicap));
+                    for (x, y) in points {
+                        root.push_str(&format!("[{}{}]", (b'a' + x) as char, (b'a' + y) as char));
+                    }
+                    root.push_str("PL[W]");
+                    next_color = "W";
+                }
+            }
+            Some(&"STO") => {
+                let color = match field(&fields, 3)? {
+                    1 => "B",
+                    2 => "W",
+                    _ => return Err(invalid(line)),
+                };
+                let x = field(&fields, 4)?;
+                let y = field(&fields, 5)?;
+                if x >= 19 || y >= 19 {
+                    return Err(invalid(line));
+                }
+                moves.push_str(&format!(
+                    ";{}[{}{}]",
+                    color,
+                    (b'a' + x) as char,
+                    (b'a' + y) as char
+                ));
+                next_color = opponent(color);
+            }
+            Some(&"SKI") => {
+                moves.push_str(&format!(";{}[]", next_color));
+                next_color = opponent(next_color);
+            }
+            _ => {}
+        }
+    }
+    Ok(format!("({}{})", root, moves))
+}
+
+fn field(fields: &[&str], index: usize) -> Result<u8, MakeSvgError> {
+    fields
+        .get(index)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid(&fields.join(" ")))
+}
+
+fn invalid(line: &str) -> MakeSvgError {
+    MakeSvgError::GibParseError(line.to_string())
+}
+
+fn opponent(color: &str) -> &'static str {
+    if color == "B" {
+        "W"
+    } else {
+        "B"
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
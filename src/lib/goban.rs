@@ -1,16 +1,34 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
 
 use sgf_parse::{go, SgfNode};
 
+use crate::board_transform::BoardTransform;
 use crate::errors::MakeSvgError;
+use crate::game_info::GameInfo;
+use crate::handicap::handicap_points;
 use crate::node_description::{NodeDescription, NodePathStep};
+use crate::parse::parse_go;
 
+/// The board state at a node of an SGF game, with the markup set at that node.
+#[derive(Clone)]
 pub struct Goban {
     size: (u8, u8),
     stones: HashMap<(u8, u8), StoneColor>,
+    setup_stones: HashSet<(u8, u8)>,
     stones_before_move: HashMap<u64, HashSet<Stone>>,
     moves: Vec<(u64, Stone)>,
+    passes: Vec<(u64, StoneColor)>,
     move_number: u64,
+    last_move: Option<(u8, u8)>,
+    ko: Option<(u8, u8)>,
+    last_captures: Vec<(u8, u8)>,
+    comment: Option<String>,
+    move_comments: Vec<(u64, String)>,
+    is_last_node: bool,
+    game_info: GameInfo,
+    captures_black: u32,
+    captures_white: u32,
     marks: HashSet<(u8, u8)>,
     triangles: HashSet<(u8, u8)>,
     circles: HashSet<(u8, u8)>,
@@ -19,7 +37,11 @@ pub struct Goban {
     lines: HashSet<((u8, u8), (u8, u8))>,
     arrows: HashSet<((u8, u8), (u8, u8))>,
     dimmed: HashSet<(u8, u8)>,
+    black_territory: HashSet<(u8, u8)>,
+    white_territory: HashSet<(u8, u8)>,
     labels: HashMap<(u8, u8), String>,
+    view: HashSet<(u8, u8)>,
+    dropped_points: Vec<(u8, u8)>,
 }
 
 impl Goban {
@@ -38,27 +60,35 @@ impl Goban {
         (15, 15),
     ];
 
+    /// Parse `sgf` and play through the first game to the described node.
+    pub fn from_sgf(sgf: &str, node_description: &NodeDescription) -> Result<Self, MakeSvgError> {
+        let collection = parse_go(sgf)?;
+        Self::from_node_in_collection(node_description, &collection, 0, BoardTransform::Identity)
+    }
+
     pub fn from_node_in_collection(
         node_description: &NodeDescription,
         collection: &[SgfNode<go::Prop>],
+        game_index: usize,
+        transform: BoardTransform,
     ) -> Result<Self, MakeSvgError> {
+        if collection.is_empty() {
+            return Err(MakeSvgError::InsufficientSgfNodes);
+        }
         let mut sgf_node = collection
-            .iter()
-            .next()
-            .ok_or(MakeSvgError::InsufficientSgfNodes)?;
+            .get(game_index)
+            .ok_or(MakeSvgError::MissingGame)?;
 
-        let board_size = get_board_size(sgf_node);
-        let mut goban = Goban::new(board_size);
-        goban.process_node(sgf_node)?;
+        let mut goban = Goban::from_root(sgf_node)?;
 
         for step in &node_description.steps {
             match step {
                 NodePathStep::Advance(n) => {
-                    for _ in 0..*n {
+                    for advanced in 0..*n {
                         sgf_node = sgf_node
                             .children()
                             .next()
-                            .ok_or(MakeSvgError::InsufficientSgfNodes)?;
+                            .ok_or(MakeSvgError::NodeOutOfRange(*n, advanced))?;
                         goban.process_node(sgf_node)?;
                     }
                 }
@@ -80,6 +110,10 @@ impl Goban {
                 }
             }
         }
+        goban.is_last_node = sgf_node.children.is_empty();
+        if transform != BoardTransform::Identity {
+            goban.apply_transform(transform);
+        }
         Ok(goban)
     }
 
@@ -107,10 +141,116 @@ impl Goban {
         self.stones.get(&(x, y)).copied()
     }
 
+    /// The color of the stone at `point`, or `None` if it's empty or off the board.
+    pub fn stone_at(&self, point: (u8, u8)) -> Option<StoneColor> {
+        if !self.is_on_board(point) {
+            return None;
+        }
+        self.stone_color(point.0, point.1)
+    }
+
+    /// Whether `point` is an intersection on the board.
+    pub fn is_on_board(&self, point: (u8, u8)) -> bool {
+        point.0 < self.size.0 && point.1 < self.size.1
+    }
+
+    /// Move numbers and colors of passes played so far.
+    pub fn passes(&self) -> impl Iterator<Item = (u64, StoneColor)> + '_ {
+        self.passes.iter().copied()
+    }
+
+    /// Whether the stone at the given point was placed by setup (AB or AW) rather than played.
+    pub fn is_setup_stone(&self, x: u8, y: u8) -> bool {
+        self.setup_stones.contains(&(x, y))
+    }
+
     pub fn moves(&self) -> impl Iterator<Item = (u64, Stone)> + '_ {
         self.moves.iter().copied()
     }
 
+    /// The number of moves played so far, including passes.
+    pub fn move_number(&self) -> u64 {
+        self.move_number
+    }
+
+    /// The point played in the current node, if any.
+    pub fn last_move(&self) -> Option<(u8, u8)> {
+        self.last_move
+    }
+
+    /// The point where an immediate recapture is forbidden by the ko rule, if any.
+    pub fn ko(&self) -> Option<(u8, u8)> {
+        self.ko
+    }
+
+    /// The points where stones were captured by the move in the current node, in order.
+    pub fn last_captures(&self) -> &[(u8, u8)] {
+        &self.last_captures
+    }
+
+    /// The comment on the current node, if any.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// The non-empty comments on nodes with moves, up to the current node, with the number of
+    /// the move each was made on.
+    pub fn move_comments(&self) -> &[(u64, String)] {
+        &self.move_comments
+    }
+
+    /// Whether the current node is the end of its variation.
+    pub fn is_last_node(&self) -> bool {
+        self.is_last_node
+    }
+
+    /// Game information from the root node.
+    pub fn game_info(&self) -> &GameInfo {
+        &self.game_info
+    }
+
+    /// The number of white stones captured by black so far.
+    pub fn captures_black(&self) -> u32 {
+        self.captures_black
+    }
+
+    /// The number of black stones captured by white so far.
+    pub fn captures_white(&self) -> u32 {
+        self.captures_white
+    }
+
+    /// The number of liberties of the group containing the stone at `point`.
+    ///
+    /// Returns `None` if there's no stone at `point`.
+    pub fn liberties(&self, point: (u8, u8)) -> Option<u32> {
+        let group = self.group(point)?;
+        let liberties: HashSet<(u8, u8)> = group
+            .iter()
+            .flat_map(|&p| self.neighbors(p))
+            .filter(|neighbor| !self.stones.contains_key(neighbor))
+            .collect();
+        Some(liberties.len() as u32)
+    }
+
+    /// The points of the group of connected stones at `point`, or `None` if it's empty.
+    pub fn group(&self, point: (u8, u8)) -> Option<HashSet<(u8, u8)>> {
+        let group_color = *self.stones.get(&point)?;
+        let mut group = HashSet::new();
+        let mut to_process = VecDeque::new();
+        to_process.push_back(point);
+        while let Some(p) = to_process.pop_back() {
+            if !group.insert(p) {
+                continue;
+            }
+            for neighbor in self.neighbors(p) {
+                if self.stones.get(&neighbor) == Some(&group_color) && !group.contains(&neighbor) {
+                    to_process.push_back(neighbor);
+                }
+            }
+        }
+        Some(group)
+    }
+
     pub fn hoshi_points(&self) -> impl Iterator<Item = (u8, u8)> {
         match self.size {
             (9, 9) => Self::NINE_HOSHIS.iter().copied(),
@@ -148,6 +288,14 @@ impl Goban {
         self.dimmed.iter().copied()
     }
 
+    pub fn black_territory(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.black_territory.iter().copied()
+    }
+
+    pub fn white_territory(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.white_territory.iter().copied()
+    }
+
     pub fn lines(&self) -> impl Iterator<Item = ((u8, u8), (u8, u8))> + '_ {
         self.lines.iter().copied()
     }
@@ -156,17 +304,80 @@ impl Goban {
         self.arrows.iter().copied()
     }
 
+    /// The points made visible by the SGF view (VW) property, or none if the whole board is
+    /// visible.
+    pub fn view(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.view.iter().copied()
+    }
+
+    /// Points outside the board which were referenced by stones or markup, and left out.
+    ///
+    /// These are in the SGF's coordinates, before any transform. Moves off the board are
+    /// skipped entirely.
+    pub fn dropped_points(&self) -> &[(u8, u8)] {
+        &self.dropped_points
+    }
+
     pub fn labels(&self) -> impl Iterator<Item = (&(u8, u8), &String)> {
         self.labels.iter()
     }
 
+    /// Everything annotated at `point` in the current node, along with the moves played there.
+    pub fn markup_at(&self, point: (u8, u8)) -> MarkupSet {
+        MarkupSet {
+            mark: self.marks.contains(&point),
+            triangle: self.triangles.contains(&point),
+            circle: self.circles.contains(&point),
+            square: self.squares.contains(&point),
+            selected: self.selected.contains(&point),
+            dimmed: self.dimmed.contains(&point),
+            label: self.labels.get(&point).cloned(),
+            move_numbers: self
+                .moves
+                .iter()
+                .filter(|(_, stone)| (stone.x, stone.y) == point)
+                .map(|(n, _)| *n)
+                .collect(),
+        }
+    }
+
+    /// The board at the root node of a game, with any handicap stones placed.
+    fn from_root(sgf_node: &SgfNode<go::Prop>) -> Result<Self, MakeSvgError> {
+        let board_size = get_board_size(sgf_node);
+        let mut goban = Goban::new(board_size);
+        goban.game_info = GameInfo::from_root(sgf_node);
+        goban.process_node(sgf_node)?;
+        if let Some(go::Prop::HA(handicap)) = sgf_node.get_property("HA") {
+            // Some records give the handicap without placing the stones.
+            if sgf_node.get_property("AB").is_none() {
+                let handicap = u8::try_from(*handicap).unwrap_or(0);
+                for (x, y) in handicap_points(board_size, handicap) {
+                    goban.add_stone(Stone::new(x, y, StoneColor::Black))?;
+                    goban.setup_stones.insert((x, y));
+                }
+            }
+        }
+        Ok(goban)
+    }
+
     fn new(board_size: (u8, u8)) -> Self {
         Self {
             size: board_size,
             stones: HashMap::new(),
+            setup_stones: HashSet::new(),
             stones_before_move: HashMap::new(),
             moves: Vec::new(),
+            passes: Vec::new(),
             move_number: 0,
+            last_move: None,
+            ko: None,
+            last_captures: Vec::new(),
+            comment: None,
+            move_comments: Vec::new(),
+            is_last_node: false,
+            game_info: GameInfo::default(),
+            captures_black: 0,
+            captures_white: 0,
             marks: HashSet::new(),
             triangles: HashSet::new(),
             circles: HashSet::new(),
@@ -175,86 +386,165 @@ impl Goban {
             lines: HashSet::new(),
             arrows: HashSet::new(),
             dimmed: HashSet::new(),
+            black_territory: HashSet::new(),
+            white_territory: HashSet::new(),
             labels: HashMap::new(),
+            view: HashSet::new(),
+            dropped_points: Vec::new(),
         }
     }
 
+    /// Remap every point on the board with the given transform.
+    fn apply_transform(&mut self, transform: BoardTransform) {
+        let size = self.size;
+        let point = |p: (u8, u8)| transform.apply(p, size);
+        let stone = |s: Stone| {
+            let (x, y) = point((s.x, s.y));
+            Stone::new(x, y, s.color)
+        };
+        let points = |points: &mut HashSet<(u8, u8)>| {
+            *points = points.drain().map(point).collect();
+        };
+        let pairs = |pairs: &mut HashSet<(_, _)>| {
+            *pairs = pairs
+                .drain()
+                .map(|(p1, p2)| (point(p1), point(p2)))
+                .collect();
+        };
+        self.size = transform.apply_to_size(size);
+        self.stones = self.stones.drain().map(|(p, c)| (point(p), c)).collect();
+        points(&mut self.setup_stones);
+        for stones in self.stones_before_move.values_mut() {
+            *stones = stones.drain().map(stone).collect();
+        }
+        for (_, s) in self.moves.iter_mut() {
+            *s = stone(*s);
+        }
+        self.last_move = self.last_move.map(point);
+        self.ko = self.ko.map(point);
+        self.last_captures = self.last_captures.drain(..).map(point).collect();
+        self.last_captures.sort_unstable();
+        points(&mut self.marks);
+        points(&mut self.triangles);
+        points(&mut self.circles);
+        points(&mut self.squares);
+        points(&mut self.selected);
+        points(&mut self.dimmed);
+        points(&mut self.black_territory);
+        points(&mut self.white_territory);
+        pairs(&mut self.lines);
+        pairs(&mut self.arrows);
+        self.labels = self.labels.drain().map(|(p, t)| (point(p), t)).collect();
+        points(&mut self.view);
+    }
+
     fn process_node(&mut self, sgf_node: &SgfNode<go::Prop>) -> Result<(), MakeSvgError> {
+        self.last_move = None;
+        self.ko = None;
+        self.last_captures.clear();
+        self.comment = None;
         self.marks.clear();
         self.triangles.clear();
         self.circles.clear();
         self.squares.clear();
         self.selected.clear();
         self.dimmed.clear();
+        self.black_territory.clear();
+        self.white_territory.clear();
         self.labels.clear();
         self.lines.clear();
         self.arrows.clear();
+        let mut move_number_override = None;
+        // Whether a move or pass was recorded, which a move off the board isn't.
+        let mut has_move = false;
         for prop in sgf_node.properties() {
             match prop {
-                go::Prop::B(go::Move::Move(point)) => {
-                    if !self.is_tt_pass(*point) {
-                        self.play_stone(Stone::new(point.x, point.y, StoneColor::Black))?;
-                    }
+                // Moves off the board are skipped, rather than counted as passes.
+                go::Prop::B(go::Move::Move(point)) | go::Prop::W(go::Move::Move(point))
+                    if !self.is_tt_pass(*point) && !self.check_on_board(point) => {}
+                go::Prop::B(go::Move::Move(point)) if !self.is_tt_pass(*point) => {
+                    self.play_stone(Stone::new(point.x, point.y, StoneColor::Black))?;
+                    has_move = true;
                 }
-                go::Prop::W(go::Move::Move(point)) => {
-                    if !self.is_tt_pass(*point) {
-                        self.play_stone(Stone::new(point.x, point.y, StoneColor::White))?;
-                    }
+                go::Prop::W(go::Move::Move(point)) if !self.is_tt_pass(*point) => {
+                    self.play_stone(Stone::new(point.x, point.y, StoneColor::White))?;
+                    has_move = true;
+                }
+                go::Prop::B(_) => {
+                    self.pass(StoneColor::Black);
+                    has_move = true;
+                }
+                go::Prop::W(_) => {
+                    self.pass(StoneColor::White);
+                    has_move = true;
                 }
                 go::Prop::AB(points) => {
-                    for point in points.iter() {
-                        self.add_stone(Stone::new(point.x, point.y, StoneColor::Black))?;
+                    for (x, y) in self.points_on_board(points) {
+                        self.add_stone(Stone::new(x, y, StoneColor::Black))?;
+                        self.setup_stones.insert((x, y));
                     }
                 }
                 go::Prop::AW(points) => {
-                    for point in points.iter() {
-                        self.add_stone(Stone::new(point.x, point.y, StoneColor::White))?;
+                    for (x, y) in self.points_on_board(points) {
+                        self.add_stone(Stone::new(x, y, StoneColor::White))?;
+                        self.setup_stones.insert((x, y));
                     }
                 }
                 go::Prop::AE(points) => {
-                    for point in points.iter() {
-                        self.clear_point((point.x, point.y));
+                    for point in self.points_on_board(points) {
+                        self.clear_point(point);
                     }
                 }
-                go::Prop::MN(num) => self.set_move_number(*num as u64),
-                go::Prop::MA(points) => self.marks = points.iter().map(|p| (p.x, p.y)).collect(),
-                go::Prop::TR(points) => {
-                    self.triangles = points.iter().map(|p| (p.x, p.y)).collect()
+                go::Prop::C(text) => self.comment = Some(text.to_string()),
+                go::Prop::MN(num) => move_number_override = u64::try_from(*num).ok(),
+                go::Prop::MA(points) => self.marks = self.points_on_board(points),
+                go::Prop::TR(points) => self.triangles = self.points_on_board(points),
+                go::Prop::CR(points) => self.circles = self.points_on_board(points),
+                go::Prop::SQ(points) => self.squares = self.points_on_board(points),
+                go::Prop::SL(points) => self.selected = self.points_on_board(points),
+                go::Prop::DD(points) => self.dimmed = self.points_on_board(points),
+                // VW stays in effect until changed, and an empty VW[] restores the whole board.
+                go::Prop::VW(points) => self.view = self.points_on_board(points),
+                // sgf_parse doesn't accept the empty value in VW[].
+                go::Prop::Invalid(identifier, values)
+                    if identifier == "VW" && values.iter().all(|value| value.is_empty()) =>
+                {
+                    self.view.clear()
                 }
-                go::Prop::CR(points) => self.circles = points.iter().map(|p| (p.x, p.y)).collect(),
-                go::Prop::SQ(points) => self.squares = points.iter().map(|p| (p.x, p.y)).collect(),
-                go::Prop::SL(points) => self.selected = points.iter().map(|p| (p.x, p.y)).collect(),
-                go::Prop::DD(points) => self.dimmed = points.iter().map(|p| (p.x, p.y)).collect(),
+                go::Prop::TB(points) => self.black_territory = self.points_on_board(points),
+                go::Prop::TW(points) => self.white_territory = self.points_on_board(points),
                 go::Prop::LB(labels) => {
                     self.labels = labels
                         .iter()
+                        .filter(|(p, _)| self.check_on_board(p))
                         .map(|(p, t)| ((p.x, p.y), t.to_string()))
                         .collect()
                 }
-                go::Prop::LN(pairs) => {
-                    self.lines = pairs
-                        .iter()
-                        .map(|(p1, p2)| ((p1.x, p1.y), (p2.x, p2.y)))
-                        .collect()
-                }
-                go::Prop::AR(pairs) => {
-                    self.arrows = pairs
-                        .iter()
-                        .map(|(p1, p2)| ((p1.x, p1.y), (p2.x, p2.y)))
-                        .collect()
-                }
+                go::Prop::LN(pairs) => self.lines = self.pairs_on_board(pairs),
+                go::Prop::AR(pairs) => self.arrows = self.pairs_on_board(pairs),
                 _ => {}
             }
         }
+        // MN gives the number of the move in this node, whichever order the properties are in.
+        if let Some(num) = move_number_override {
+            self.set_move_number(num, has_move);
+        }
+        if has_move {
+            if let Some(comment) = &self.comment {
+                if !comment.trim().is_empty() {
+                    self.move_comments.push((self.move_number, comment.clone()));
+                }
+            }
+        }
 
         Ok(())
     }
 
     fn add_stone(&mut self, stone: Stone) -> Result<(), MakeSvgError> {
-        if stone.x > self.size.0 || stone.y > self.size.1 {
-            return Err(MakeSvgError::InvalidMoveError);
-        }
         let key = (stone.x, stone.y);
+        if !self.is_on_board(key) {
+            return Err(MakeSvgError::PointOffBoard(key));
+        }
         if self.stones.contains_key(&key) {
             return Err(MakeSvgError::InvalidMoveError);
         }
@@ -276,32 +566,100 @@ impl Goban {
                 .collect(),
         );
         self.add_stone(stone)?;
-        let opponent_color = match stone.color {
-            StoneColor::Black => StoneColor::White,
-            StoneColor::White => StoneColor::Black,
-        };
+        let opponent_color = stone.color.opposite();
         // Remove any neighboring groups with no liberties.
         let key = (stone.x, stone.y);
+        let mut captured = HashSet::new();
         for neighbor in self.neighbors(key) {
             if let Some(color) = self.stones.get(&neighbor) {
                 if *color == opponent_color {
-                    self.process_captures(neighbor);
+                    captured.extend(self.process_captures(neighbor));
                 }
             }
         }
         // Now remove the played stone if still neccessary
-        self.process_captures(key);
+        let suicided = self.process_captures(key);
+        self.last_captures = captured.iter().chain(&suicided).copied().collect();
+        self.last_captures.sort_unstable();
+        // A lone stone which captured a single stone and is left in atari can be recaptured
+        // immediately, so the captured point is a ko.
+        if captured.len() == 1 {
+            let is_ko = self.neighbors(key).all(|neighbor| {
+                captured.contains(&neighbor) || self.stones.get(&neighbor) == Some(&opponent_color)
+            });
+            if is_ko {
+                self.ko = captured.into_iter().next();
+            }
+        }
         self.move_number += 1;
         self.moves.push((self.move_number, stone));
+        self.last_move = Some(key);
 
         Ok(())
     }
 
+    /// Whether `point` is on the board, recording it in `dropped_points` if not.
+    fn check_on_board(&mut self, point: &go::Point) -> bool {
+        let point = (point.x, point.y);
+        if self.is_on_board(point) {
+            return true;
+        }
+        if !self.dropped_points.contains(&point) {
+            self.dropped_points.push(point);
+        }
+        false
+    }
+
+    fn points_on_board(&mut self, points: &HashSet<go::Point>) -> HashSet<(u8, u8)> {
+        points
+            .iter()
+            .filter(|p| self.check_on_board(p))
+            .map(|p| (p.x, p.y))
+            .collect()
+    }
+
+    /// Lines and arrows with both ends on the board.
+    fn pairs_on_board(
+        &mut self,
+        pairs: &HashSet<(go::Point, go::Point)>,
+    ) -> HashSet<((u8, u8), (u8, u8))> {
+        pairs
+            .iter()
+            // Not `&&`, so an off-board end is recorded even when the other one is too.
+            .filter(|(p1, p2)| self.check_on_board(p1) & self.check_on_board(p2))
+            .map(|(p1, p2)| ((p1.x, p1.y), (p2.x, p2.y)))
+            .collect()
+    }
+
+    fn pass(&mut self, color: StoneColor) {
+        self.move_number += 1;
+        self.passes.push((self.move_number, color));
+    }
+
     fn clear_point(&mut self, point: (u8, u8)) {
         self.stones.remove(&point);
+        self.setup_stones.remove(&point);
     }
 
-    fn set_move_number(&mut self, num: u64) {
+    /// Set the current move number to `num`, renumbering the move just played if
+    /// `renumber_last_move` is set, so later moves count on from it.
+    fn set_move_number(&mut self, num: u64, renumber_last_move: bool) {
+        if renumber_last_move {
+            let old = self.move_number;
+            if let Some((n, _)) = self.moves.last_mut().filter(|(n, _)| *n == old) {
+                *n = num;
+            }
+            if let Some((n, _)) = self.passes.last_mut().filter(|(n, _)| *n == old) {
+                *n = num;
+            }
+            let stones = old
+                .checked_sub(1)
+                .and_then(|before| self.stones_before_move.remove(&before));
+            if let Some(stones) = stones {
+                self.stones_before_move
+                    .insert(num.saturating_sub(1), stones);
+            }
+        }
         self.move_number = num;
     }
 
@@ -324,10 +682,11 @@ impl Goban {
         neighbors.into_iter()
     }
 
-    fn process_captures(&mut self, start_point: (u8, u8)) {
+    /// Remove the group at `start_point` if it has no liberties, returning the removed points.
+    fn process_captures(&mut self, start_point: (u8, u8)) -> HashSet<(u8, u8)> {
         let group_color = match self.stones.get(&start_point) {
-            Some(color) => color,
-            None => return,
+            Some(color) => *color,
+            None => return HashSet::new(),
         };
         let mut group = HashSet::new();
         let mut to_process = VecDeque::new();
@@ -339,17 +698,23 @@ impl Goban {
                     continue;
                 }
                 match self.stones.get(&neighbor) {
-                    None => return,
-                    Some(c) if c == group_color => {
+                    None => return HashSet::new(),
+                    Some(c) if *c == group_color => {
                         to_process.push_back(neighbor);
                     }
                     _ => {}
                 }
             }
         }
-        for stone in group {
-            self.stones.remove(&stone);
+        // Suicide counts as a capture for the opponent too.
+        match group_color {
+            StoneColor::Black => self.captures_white += group.len() as u32,
+            StoneColor::White => self.captures_black += group.len() as u32,
+        }
+        for stone in &group {
+            self.clear_point(*stone);
         }
+        group
     }
 
     fn is_tt_pass(&self, point: go::Point) -> bool {
@@ -357,13 +722,51 @@ impl Goban {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum StoneColor {
     Black,
     White,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+impl StoneColor {
+    /// The other player's color.
+    pub fn opposite(self) -> StoneColor {
+        match self {
+            StoneColor::Black => StoneColor::White,
+            StoneColor::White => StoneColor::Black,
+        }
+    }
+
+    /// Plain `"black"` or `"white"`, the color of a stone of this color.
+    pub fn color(self) -> &'static str {
+        match self {
+            StoneColor::Black => "black",
+            StoneColor::White => "white",
+        }
+    }
+
+    /// Plain `"white"` or `"black"`, whichever stands out against a stone of this color.
+    pub fn contrast_color(self) -> &'static str {
+        self.opposite().color()
+    }
+}
+
+/// The markup at a single point. See `Goban::markup_at`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkupSet {
+    pub mark: bool,
+    pub triangle: bool,
+    pub circle: bool,
+    pub square: bool,
+    pub selected: bool,
+    pub dimmed: bool,
+    pub label: Option<String>,
+    /// The numbers of the moves played at the point so far, in order.
+    pub move_numbers: Vec<u64>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize)]
 pub struct Stone {
     pub x: u8,
     pub y: u8,
@@ -376,7 +779,64 @@ impl Stone {
     }
 }
 
-fn get_board_size(sgf_node: &SgfNode<go::Prop>) -> (u8, u8) {
+/// Count the moves along the main line of the selected game in `sgf`, including passes.
+pub fn game_length(sgf: &str, game_index: usize) -> Result<u64, MakeSvgError> {
+    let collection = parse_go(sgf)?;
+    let mut sgf_node = collection
+        .get(game_index)
+        .ok_or(MakeSvgError::MissingGame)?;
+    let mut moves = 0;
+    loop {
+        if plays_move(sgf_node) {
+            moves += 1;
+        }
+        match sgf_node.children().next() {
+            Some(child) => sgf_node = child,
+            None => return Ok(moves),
+        }
+    }
+}
+
+/// The board after each move along the main line of the selected game in `sgf`, including
+/// passes.
+///
+/// The sgf is parsed once up front and the positions are played out lazily. Iteration ends
+/// after the first node which can't be played, such as a move onto an occupied point.
+pub fn game_positions(
+    sgf: &str,
+    game_index: usize,
+) -> Result<impl Iterator<Item = Result<Goban, MakeSvgError>>, MakeSvgError> {
+    let collection = parse_go(sgf)?;
+    let root = collection
+        .into_iter()
+        .nth(game_index)
+        .ok_or(MakeSvgError::MissingGame)?;
+    let mut goban = Goban::from_root(&root)?;
+    goban.is_last_node = root.children.is_empty();
+    let root_position = plays_move(&root).then(|| Ok(goban.clone()));
+    let mut next_node = root.children.into_iter().next();
+    let positions = std::iter::from_fn(move || loop {
+        let sgf_node = next_node.take()?;
+        if let Err(e) = goban.process_node(&sgf_node) {
+            return Some(Err(e));
+        }
+        let is_move = plays_move(&sgf_node);
+        next_node = sgf_node.children.into_iter().next();
+        if is_move {
+            goban.is_last_node = next_node.is_none();
+            return Some(Ok(goban.clone()));
+        }
+    });
+    Ok(root_position.into_iter().chain(positions))
+}
+
+fn plays_move(sgf_node: &SgfNode<go::Prop>) -> bool {
+    sgf_node
+        .properties()
+        .any(|prop| matches!(prop, go::Prop::B(_) | go::Prop::W(_)))
+}
+
+pub(crate) fn get_board_size(sgf_node: &SgfNode<go::Prop>) -> (u8, u8) {
     match sgf_node.get_property("SZ") {
         Some(go::Prop::SZ(size)) => *size,
         None => (19, 19),
@@ -8,7 +8,13 @@ use crate::make_svg::MakeSvgOptions;
 #[derive(Debug, Clone)]
 pub enum GobanRange {
     ShrinkWrap,
+    Shrink {
+        margin: u8,
+    },
     FullBoard,
+    /// The bounding box of the points in the SGF view (VW) property, or the full board if
+    /// there's no view.
+    FromSgf,
     Ranged(Range<u8>, Range<u8>),
 }
 
@@ -22,32 +28,7 @@ impl GobanRange {
         match self {
             Self::FullBoard => Ok((0..goban_size.0, 0..goban_size.1)),
             Self::ShrinkWrap => {
-                let mut points: HashSet<_> = goban.stones().map(|s| (s.x, s.y)).collect();
-                if options.draw_marks {
-                    points.extend(goban.marks());
-                }
-                if options.draw_triangles {
-                    points.extend(goban.triangles());
-                }
-                if options.draw_circles {
-                    points.extend(goban.circles());
-                }
-                if options.draw_squares {
-                    points.extend(goban.squares());
-                }
-                if options.draw_selected {
-                    points.extend(goban.selected());
-                }
-                if options.draw_labels {
-                    points.extend(goban.labels().map(|(p, _)| p))
-                }
-                if options.draw_lines {
-                    points.extend(goban.lines().flat_map(|(p1, p2)| vec![p1, p2]))
-                }
-                if options.draw_arrows {
-                    points.extend(goban.arrows().flat_map(|(p1, p2)| vec![p1, p2]))
-                }
-                // Don't necessarily include dimmed points!
+                let points = content_points(goban, options);
                 let x_start = {
                     let p = points
                         .iter()
@@ -100,6 +81,32 @@ impl GobanRange {
                 };
                 Ok((x_start..x_end, y_start..y_end))
             }
+            Self::Shrink { margin } => {
+                let points = content_points(goban, options);
+                let xs = points.iter().map(|&(x, _)| x);
+                let ys = points.iter().map(|&(_, y)| y);
+                match (xs.clone().min(), xs.max(), ys.clone().min(), ys.max()) {
+                    (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) => Ok((
+                        x_min.saturating_sub(*margin)
+                            ..(x_max + 1).saturating_add(*margin).min(goban_size.0),
+                        y_min.saturating_sub(*margin)
+                            ..(y_max + 1).saturating_add(*margin).min(goban_size.1),
+                    )),
+                    _ => Ok((0..goban_size.0, 0..goban_size.1)),
+                }
+            }
+            Self::FromSgf => {
+                let view: Vec<(u8, u8)> = goban.view().collect();
+                let xs = view.iter().map(|&(x, _)| x);
+                let ys = view.iter().map(|&(_, y)| y);
+                match (xs.clone().min(), xs.max(), ys.clone().min(), ys.max()) {
+                    (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) => Ok((
+                        x_min.min(goban_size.0)..(x_max + 1).min(goban_size.0),
+                        y_min.min(goban_size.1)..(y_max + 1).min(goban_size.1),
+                    )),
+                    _ => Ok((0..goban_size.0, 0..goban_size.1)),
+                }
+            }
             Self::Ranged(a, b) => {
                 if a.end > goban_size.0 || b.end > goban_size.1 {
                     Err(MakeSvgError::InvalidRange)
@@ -111,10 +118,53 @@ impl GobanRange {
     }
 }
 
+/// Points with stones or drawn markup.
+fn content_points(goban: &Goban, options: &MakeSvgOptions) -> HashSet<(u8, u8)> {
+    let mut points: HashSet<_> = goban.stones().map(|s| (s.x, s.y)).collect();
+    if options.draw_marks {
+        points.extend(goban.marks());
+    }
+    if options.draw_triangles {
+        points.extend(goban.triangles());
+    }
+    if options.draw_circles {
+        points.extend(goban.circles());
+    }
+    if options.draw_squares {
+        points.extend(goban.squares());
+    }
+    if options.draw_selected {
+        points.extend(goban.selected());
+    }
+    if options.draw_territory {
+        points.extend(goban.black_territory());
+        points.extend(goban.white_territory());
+    }
+    if options.draw_labels {
+        points.extend(goban.labels().map(|(p, _)| p))
+    }
+    if options.draw_lines {
+        points.extend(goban.lines().flat_map(|(p1, p2)| vec![p1, p2]))
+    }
+    if options.draw_arrows {
+        points.extend(goban.arrows().flat_map(|(p1, p2)| vec![p1, p2]))
+    }
+    points.extend(options.highlight_moves.iter().copied());
+    for &point in &options.outline_groups {
+        points.extend(goban.group(point).into_iter().flatten());
+    }
+    points.extend(options.solution_marks.iter().map(|&(p, _)| p));
+    // Don't necessarily include dimmed points!
+    points
+}
+
 impl std::str::FromStr for GobanRange {
     type Err = UsageError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "view" {
+            return Ok(GobanRange::FromSgf);
+        }
         let parse_byte = |b: u8| match b {
             b'a'..=b'z' => Ok(b - b'a'),
             _ => Err(UsageError::InvalidRange),
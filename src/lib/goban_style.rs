@@ -2,26 +2,31 @@ use minidom::Element;
 
 use crate::errors::MakeSvgError;
 use crate::goban::StoneColor;
-use crate::make_svg::NAMESPACE;
+use crate::make_svg::{format_float, NAMESPACE};
 
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct GobanStyle {
     line_color: String,
+    #[serde(default = "default_line_width")]
     line_width: f64,
+    #[serde(default = "default_hoshi_radius")]
     hoshi_radius: f64,
+    hoshi_fill: Option<String>,
     background_fill: String,
+    intersection_fill: Option<String>,
     label_color: String,
     black_stone_fill: Option<String>,
     white_stone_fill: Option<String>,
     black_stone_stroke: Option<String>,
     white_stone_stroke: Option<String>,
+    #[serde(default = "default_markup_stroke_width")]
     markup_stroke_width: f64,
     black_stone_markup_color: String,
     white_stone_markup_color: String,
-    empty_markup_color: String,
-    black_stone_selected_color: String,
-    white_stone_selected_color: String,
-    empty_selected_color: String,
+    empty_markup_color: Option<String>,
+    black_stone_selected_color: Option<String>,
+    white_stone_selected_color: Option<String>,
+    empty_selected_color: Option<String>,
     defs: Option<String>,
 }
 
@@ -34,14 +39,28 @@ impl GobanStyle {
         self.line_width
     }
 
-    pub fn hoshi_radius(&self) -> f64 {
-        self.hoshi_radius
+    /// Radius of the hoshi dots, or `None` if the style doesn't draw hoshi (a radius of 0).
+    pub fn hoshi_radius(&self) -> Option<f64> {
+        (self.hoshi_radius > 0.0).then_some(self.hoshi_radius)
+    }
+
+    /// Fill for the hoshi dots, which defaults to the line color.
+    pub fn hoshi_fill(&self) -> &str {
+        self.hoshi_fill.as_deref().unwrap_or(&self.line_color)
     }
 
     pub fn background_fill(&self) -> &str {
         &self.background_fill
     }
 
+    /// Fill for patches hiding the grid behind numbers and labels on empty points. This
+    /// defaults to the background fill, but should be a plain color if that's a gradient.
+    pub fn intersection_fill(&self) -> &str {
+        self.intersection_fill
+            .as_deref()
+            .unwrap_or(&self.background_fill)
+    }
+
     pub fn label_color(&self) -> &str {
         &self.label_color
     }
@@ -60,11 +79,16 @@ impl GobanStyle {
         }
     }
 
+    /// Color for markup on a stone of the given color, or on an empty point. Markup on empty
+    /// points defaults to the color used on white stones.
     pub fn markup_color(&self, color: Option<StoneColor>) -> &str {
         match color {
             Some(StoneColor::Black) => &self.black_stone_markup_color,
             Some(StoneColor::White) => &self.white_stone_markup_color,
-            None => &self.empty_markup_color,
+            None => self
+                .empty_markup_color
+                .as_deref()
+                .unwrap_or(&self.white_stone_markup_color),
         }
     }
 
@@ -72,43 +96,83 @@ impl GobanStyle {
         self.markup_stroke_width
     }
 
+    /// Color for selected points, which defaults to the markup color at the point.
     pub fn selected_color(&self, color: Option<StoneColor>) -> &str {
-        match color {
+        let selected_color = match color {
             Some(StoneColor::Black) => &self.black_stone_selected_color,
             Some(StoneColor::White) => &self.white_stone_selected_color,
             None => &self.empty_selected_color,
+        };
+        selected_color
+            .as_deref()
+            .unwrap_or_else(|| self.markup_color(color))
+    }
+
+    /// Color for move numbers, which is the markup color unless that wouldn't be legible.
+    ///
+    /// If both the stone fill and markup color are plain colors with too little contrast
+    /// between them, black or white is used instead, whichever contrasts more with the stone.
+    pub fn move_number_color(&self, color: Option<StoneColor>) -> &str {
+        let markup_color = self.markup_color(color);
+        let color = match color {
+            Some(color) => color,
+            None => return markup_color,
+        };
+        // Circles without a fill are drawn black.
+        let fill = self.stone_fill(color).unwrap_or("black");
+        match (luminance(fill), luminance(markup_color)) {
+            (Some(fill), Some(markup)) if contrast_ratio(fill, markup) < MIN_CONTRAST_RATIO => {
+                if contrast_ratio(fill, 0.0) > contrast_ratio(fill, 1.0) {
+                    "black"
+                } else {
+                    "white"
+                }
+            }
+            _ => markup_color,
+        }
+    }
+
+    /// Color for move number `n`, tinted red for odd (Black's) moves and blue for even
+    /// (White's) ones.
+    ///
+    /// The untinted `move_number_color` is used if it isn't a plain color or if the tint would
+    /// leave too little contrast with the stone or background behind the number.
+    pub fn parity_move_number_color(&self, color: Option<StoneColor>, n: u64) -> String {
+        let base = self.move_number_color(color);
+        let tint = if n % 2 == 1 {
+            ODD_MOVE_TINT
+        } else {
+            EVEN_MOVE_TINT
+        };
+        let behind = match color {
+            // Circles without a fill are drawn black.
+            Some(color) => self.stone_fill(color).unwrap_or("black"),
+            None => self.background_fill(),
+        };
+        let (base_rgb, tint_rgb) = match (parse_rgb(base), parse_rgb(tint)) {
+            (Some(base_rgb), Some(tint_rgb)) => (base_rgb, tint_rgb),
+            _ => return base.to_string(),
+        };
+        let mix = |a: u8, b: u8| {
+            (f64::from(a) * (1.0 - PARITY_TINT_AMOUNT) + f64::from(b) * PARITY_TINT_AMOUNT).round()
+                as u8
+        };
+        let tinted = format!(
+            "#{:02x}{:02x}{:02x}",
+            mix(base_rgb.0, tint_rgb.0),
+            mix(base_rgb.1, tint_rgb.1),
+            mix(base_rgb.2, tint_rgb.2)
+        );
+        match (luminance(behind), luminance(&tinted)) {
+            (Some(behind), Some(number)) if contrast_ratio(behind, number) < MIN_CONTRAST_RATIO => {
+                base.to_string()
+            }
+            _ => tinted,
         }
     }
 
     pub fn defs(&self) -> Result<Vec<Element>, MakeSvgError> {
-        let linehead = Element::builder("marker", NAMESPACE)
-            .attr("id", "linehead")
-            .attr("markerWidth", "4")
-            .attr("markerHeight", "4")
-            .attr("refX", "2")
-            .attr("refY", "2")
-            .append(
-                Element::builder("circle", NAMESPACE)
-                    .attr("cx", "2")
-                    .attr("cy", "2")
-                    .attr("r", "2")
-                    .build(),
-            )
-            .build();
-        let arrowhead = Element::builder("marker", NAMESPACE)
-            .attr("id", "arrowhead")
-            .attr("markerWidth", "7")
-            .attr("markerHeight", "5")
-            .attr("refX", "7")
-            .attr("refY", "2.5")
-            .attr("orient", "auto")
-            .append(
-                Element::builder("polygon", NAMESPACE)
-                    .attr("points", "0 0, 7 2.5, 0 5")
-                    .build(),
-            )
-            .build();
-        let mut defs = vec![linehead, arrowhead];
+        let mut defs = vec![];
         if let Some(s) = &self.defs {
             // Wrap
             let wrapped = format!("<svg xmlns=\"{}\">{}</svg>", NAMESPACE, s);
@@ -119,4 +183,103 @@ impl GobanStyle {
         }
         Ok(defs)
     }
+
+    /// The defs, with the radial gradients used as stone fills moved so their highlight faces a
+    /// light at `light_angle` degrees counterclockwise from the right, e.g. 90 for light from
+    /// above. With `None` the gradients are left as the style defines them.
+    pub fn lit_defs(&self, light_angle: Option<f64>) -> Result<Vec<Element>, MakeSvgError> {
+        let mut defs = self.defs()?;
+        let light_angle = match light_angle {
+            Some(light_angle) => light_angle,
+            None => return Ok(defs),
+        };
+        let gradient_ids: Vec<&str> = [StoneColor::Black, StoneColor::White]
+            .iter()
+            .filter_map(|&color| self.stone_fill(color))
+            .filter_map(|fill| fill.strip_prefix("url(#")?.strip_suffix(')'))
+            .collect();
+        let (sin, cos) = light_angle.to_radians().sin_cos();
+        let x = format!("{}%", format_float(50.0 + HIGHLIGHT_OFFSET * cos));
+        let y = format!("{}%", format_float(50.0 - HIGHLIGHT_OFFSET * sin));
+        for def in &mut defs {
+            if def.name() == "radialGradient"
+                && def.attr("id").is_some_and(|id| gradient_ids.contains(&id))
+            {
+                def.set_attr("cx", x.as_str());
+                def.set_attr("cy", y.as_str());
+                def.set_attr("fx", x.as_str());
+                def.set_attr("fy", y.as_str());
+            }
+        }
+        Ok(defs)
+    }
+}
+
+/// Distance of a stone's highlight from its center, as a percentage of its bounding box. Light
+/// at 135 degrees puts it at 35%, 35% as in the fancy style.
+static HIGHLIGHT_OFFSET: f64 = 15.0 * std::f64::consts::SQRT_2;
+
+/// The contrast ratio below which move numbers are considered illegible.
+static MIN_CONTRAST_RATIO: f64 = 3.0;
+
+/// Colors mixed into move numbers to show whose move it was.
+static ODD_MOVE_TINT: &str = "#d02020";
+static EVEN_MOVE_TINT: &str = "#2050d0";
+
+/// How much of the tint is mixed into the move number color.
+static PARITY_TINT_AMOUNT: f64 = 0.4;
+
+/// The channels of a plain `#rgb`, `#rrggbb`, `black` or `white` color.
+pub(crate) fn parse_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    match color {
+        "black" => Some((0, 0, 0)),
+        "white" => Some((255, 255, 255)),
+        _ => {
+            let hex = color.strip_prefix('#').filter(|hex| hex.is_ascii())?;
+            let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+            match hex.len() {
+                3 => Some((
+                    channel(&hex[0..1])? * 17,
+                    channel(&hex[1..2])? * 17,
+                    channel(&hex[2..3])? * 17,
+                )),
+                6 => Some((
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                )),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Relative luminance of a color understood by `parse_rgb`.
+fn luminance(color: &str) -> Option<f64> {
+    let (r, g, b) = parse_rgb(color)?;
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+}
+
+fn contrast_ratio(a: f64, b: f64) -> f64 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn default_line_width() -> f64 {
+    0.03
+}
+
+pub(crate) fn default_hoshi_radius() -> f64 {
+    0.09
+}
+
+fn default_markup_stroke_width() -> f64 {
+    0.1
 }
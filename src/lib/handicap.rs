@@ -0,0 +1,31 @@
+/// Standard placement of `handicap` stones on a board of the given size.
+///
+/// Supports 2 to 9 stones on 9x9, 13x13 and 19x19 boards; returns an empty list otherwise.
+/// Points are sorted by row, then column.
+pub fn handicap_points(size: (u8, u8), handicap: u8) -> Vec<(u8, u8)> {
+    let edge = match size {
+        (9, 9) => 2,
+        (13, 13) | (19, 19) => 3,
+        _ => return vec![],
+    };
+    let low = edge;
+    let high = size.0 - 1 - edge;
+    let mid = (size.0 - 1) / 2;
+    let mut points = match handicap {
+        2 => vec![(high, low), (low, high)],
+        3 => vec![(high, low), (low, high), (high, high)],
+        4..=9 => vec![(low, low), (high, low), (low, high), (high, high)],
+        _ => return vec![],
+    };
+    if handicap >= 6 {
+        points.extend([(low, mid), (high, mid)]);
+    }
+    if handicap >= 8 {
+        points.extend([(mid, low), (mid, high)]);
+    }
+    if handicap % 2 == 1 && handicap >= 5 {
+        points.push((mid, mid));
+    }
+    points.sort_by_key(|&(x, y)| (y, x));
+    points
+}
@@ -0,0 +1,85 @@
+use std::convert::TryFrom;
+
+use crate::errors::MakeSvgError;
+use crate::goban::Goban;
+use crate::goban_range::GobanRange;
+use crate::make_png::{rasterize, BASE_DPI};
+use crate::make_svg::{render_goban, MakeSvgOptions};
+use crate::node_description::{NodeDescription, NodePathStep};
+use crate::parse::parse_go;
+
+/// Maximum number of nodes rendered into an animation.
+pub static MAX_ANIMATION_FRAMES: usize = 1000;
+
+/// Render an animated GIF of the main variation, one frame per node.
+///
+/// Every frame uses the board range of the final position so the frames line up. The final
+/// position is held for an extra `frame_delay_ms`. At most `MAX_ANIMATION_FRAMES` nodes are
+/// rendered.
+pub fn make_animation(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    frame_delay_ms: u16,
+) -> Result<Vec<u8>, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let mut gobans = vec![];
+    for n in 0..MAX_ANIMATION_FRAMES {
+        let node_description = NodeDescription {
+            steps: vec![NodePathStep::Advance(n)],
+        };
+        match Goban::from_node_in_collection(
+            &node_description,
+            &collection,
+            options.game_index,
+            options.board_transform(),
+        ) {
+            Ok(goban) => gobans.push(goban),
+            Err(MakeSvgError::NodeOutOfRange(..)) if n > 0 => break,
+            Err(e) => return Err(e),
+        }
+    }
+    let last_goban = gobans.last().ok_or(MakeSvgError::InsufficientSgfNodes)?;
+    let (x_range, y_range) = options.goban_range.get_ranges(last_goban, options)?;
+    let options = MakeSvgOptions {
+        goban_range: GobanRange::Ranged(x_range, y_range),
+        ..options.clone()
+    };
+
+    let mut pixmaps = gobans
+        .iter()
+        .map(|goban| rasterize(&render_goban(goban, &options)?, BASE_DPI));
+    let first = pixmaps.next().ok_or(MakeSvgError::InsufficientSgfNodes)??;
+    let (width, height) = gif_dimensions(&first)?;
+    // GIF delays are in hundredths of a second.
+    let delay = frame_delay_ms / 10;
+
+    let mut buffer = vec![];
+    {
+        let mut encoder = gif::Encoder::new(&mut buffer, width, height, &[])
+            .map_err(|e| MakeSvgError::GifError(e.into()))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| MakeSvgError::GifError(e.into()))?;
+        for (i, pixmap) in std::iter::once(Ok(first)).chain(pixmaps).enumerate() {
+            let mut pixels = pixmap?.take();
+            let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+            frame.delay = if i + 1 == gobans.len() {
+                delay.saturating_mul(2)
+            } else {
+                delay
+            };
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| MakeSvgError::GifError(e.into()))?;
+        }
+    }
+    Ok(buffer)
+}
+
+fn gif_dimensions(pixmap: &tiny_skia::Pixmap) -> Result<(u16, u16), MakeSvgError> {
+    let to_u16 = |n: u32| {
+        u16::try_from(n).map_err(|_| MakeSvgError::GifError("Image too large for GIF".into()))
+    };
+    Ok((to_u16(pixmap.width())?, to_u16(pixmap.height())?))
+}
@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+
+use crate::errors::MakeSvgError;
+use crate::goban::{Goban, StoneColor};
+use crate::make_svg::{hoshi_points, MakeSvgOptions};
+use crate::parse::parse_go;
+
+/// Render the selected node as a plain text grid.
+///
+/// Black stones are drawn as `X`, white stones as `O`, hoshi as `+` and other empty points as
+/// `.`. Only the stones are drawn; markup is ignored. Respects `options.goban_range`.
+pub fn make_ascii(sgf: &str, options: &MakeSvgOptions) -> Result<String, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let goban = Goban::from_node_in_collection(
+        &options.node_description,
+        &collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    options.check_strict(&goban)?;
+    let (x_range, y_range) = options.goban_range.get_ranges(&goban, options)?;
+    let hoshi_points: HashSet<_> = hoshi_points(&goban, options).into_iter().collect();
+    let mut output = String::new();
+    for y in y_range {
+        let row: Vec<&str> = x_range
+            .clone()
+            .map(|x| match goban.stone_color(x, y) {
+                Some(StoneColor::Black) => "X",
+                Some(StoneColor::White) => "O",
+                None if hoshi_points.contains(&(x, y)) => "+",
+                None => ".",
+            })
+            .collect();
+        output.push_str(&row.join(" "));
+        output.push('\n');
+    }
+    Ok(output)
+}
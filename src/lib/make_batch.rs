@@ -0,0 +1,18 @@
+use minidom::Element;
+use rayon::prelude::*;
+
+use crate::errors::MakeSvgError;
+use crate::make_svg::{make_svg, MakeSvgOptions};
+
+/// Render many SGF inputs in parallel, each with its own options.
+///
+/// Results are in the same order as `inputs`, and a failure only affects its own entry.
+/// Work is spread over rayon's global thread pool, so the inputs and options are shared
+/// between threads (`MakeSvgOptions` is `Sync`) and results are sent back (`Element` and
+/// `MakeSvgError` are `Send`).
+pub fn make_svg_batch(inputs: &[(&str, MakeSvgOptions)]) -> Vec<Result<Element, MakeSvgError>> {
+    inputs
+        .par_iter()
+        .map(|(sgf, options)| make_svg(sgf, options))
+        .collect()
+}
@@ -0,0 +1,135 @@
+use minidom::Element;
+
+use crate::errors::MakeSvgError;
+use crate::goban::Goban;
+use crate::goban_range::GobanRange;
+use crate::make_svg::{finish_svg, format_float, render_goban, MakeSvgOptions, NAMESPACE};
+use crate::node_description::NodeDescription;
+use crate::parse::parse_go;
+
+/// Space between the two boards, as a fraction of the width of one board.
+static COMPARISON_GAP: f64 = 0.05;
+
+/// Render two nodes of the selected game side by side, e.g. before and after a capture.
+///
+/// Both boards are drawn with `options` over the same range, so with a shrink-wrapped range
+/// they crop to everything shown on either board and line up. Each is as wide as
+/// `options.size` makes it; the combined diagram is as tall as the taller of the two. Captions such as the
+/// node's comment are drawn below each board as usual.
+pub fn make_comparison_svg(
+    sgf: &str,
+    left: NodeDescription,
+    right: NodeDescription,
+    options: &MakeSvgOptions,
+) -> Result<Element, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let gobans = [left, right]
+        .iter()
+        .map(|node_description| {
+            Goban::from_node_in_collection(
+                node_description,
+                &collection,
+                options.game_index,
+                options.board_transform(),
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (left_ranges, right_ranges) = (
+        options.goban_range.get_ranges(&gobans[0], options)?,
+        options.goban_range.get_ranges(&gobans[1], options)?,
+    );
+    let x_range =
+        left_ranges.0.start.min(right_ranges.0.start)..left_ranges.0.end.max(right_ranges.0.end);
+    let y_range =
+        left_ranges.1.start.min(right_ranges.1.start)..left_ranges.1.end.max(right_ranges.1.end);
+    // The whole document is finished at once, so the panels share style class names.
+    let panel_options = MakeSvgOptions {
+        goban_range: GobanRange::Ranged(x_range, y_range),
+        inline_styles: true,
+        coordinate_precision: None,
+        ..options.clone()
+    };
+
+    let mut panels = gobans
+        .iter()
+        .zip(["left-", "right-"])
+        .map(|(goban, id_prefix)| {
+            let mut panel = render_goban(goban, &panel_options)?;
+            prefix_ids(&mut panel, id_prefix);
+            Ok(panel)
+        })
+        .collect::<Result<Vec<_>, MakeSvgError>>()?;
+    // Both panels are the same width, however `options.size` chose it.
+    let gap = viewbox_size(&panels[0]).0 * COMPARISON_GAP;
+    let mut x = 0.0;
+    let mut height: f64 = 0.0;
+    for panel in &mut panels {
+        let (panel_width, panel_height) = viewbox_size(panel);
+        panel.set_attr("x", format_float(x));
+        panel.set_attr("y", "0");
+        panel.set_attr("width", format_float(panel_width));
+        panel.set_attr("height", format_float(panel_height));
+        x += panel_width + gap;
+        height = height.max(panel_height);
+    }
+    let width = x - gap;
+
+    let mut svg_builder = Element::builder("svg", NAMESPACE)
+        .attr(
+            "viewBox",
+            format!("0 0 {} {}", format_float(width), format_float(height)),
+        )
+        .attr("width", width.to_string());
+    if !options.transparent_background {
+        svg_builder = svg_builder.append(
+            Element::builder("rect", NAMESPACE)
+                .attr("fill", options.style.background_fill())
+                .attr("height", "100%")
+                .attr("width", "100%")
+                .attr("x", "0")
+                .attr("y", "0"),
+        );
+    }
+    Ok(finish_svg(svg_builder.append_all(panels).build(), options))
+}
+
+/// The width and height from an svg element's `viewBox`.
+fn viewbox_size(svg: &Element) -> (f64, f64) {
+    let values: Vec<f64> = svg
+        .attr("viewBox")
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    match values[..] {
+        [_, _, width, height] => (width, height),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// Prefix every id in `element` and references to them, so the ids stay unique when several
+/// diagrams are combined in one document.
+fn prefix_ids(element: &mut Element, prefix: &str) {
+    let updates: Vec<(String, String)> = element
+        .attrs()
+        .filter_map(|(name, value)| {
+            let updated = if name == "id" {
+                format!("{}{}", prefix, value)
+            } else if name.ends_with("href") && value.starts_with('#') {
+                format!("#{}{}", prefix, &value[1..])
+            } else if value.contains("url(#") {
+                value.replace("url(#", &format!("url(#{}", prefix))
+            } else {
+                return None;
+            };
+            Some((name.to_string(), updated))
+        })
+        .collect();
+    for (name, value) in updates {
+        element.set_attr(name, value);
+    }
+    for child in element.children_mut() {
+        prefix_ids(child, prefix);
+    }
+}
@@ -0,0 +1,41 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use minidom::Element;
+
+use crate::errors::MakeSvgError;
+use crate::make_svg::{render_each_figure, MakeSvgOptions};
+
+/// Render each figure of `sgf` as `make_figures` would and write it to `dir`, in a file named
+/// by `name_fn` from the figure's index (starting at 0).
+///
+/// Figures are rendered from one parse, so an error there fails the whole call. Otherwise the
+/// result has an entry per figure with the path written or the error rendering or writing it,
+/// and a failure only affects its own entry.
+pub fn render_figures_to_files<F: Fn(usize) -> String>(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    dir: &Path,
+    name_fn: F,
+) -> Result<Vec<Result<PathBuf, MakeSvgError>>, MakeSvgError> {
+    let figures = render_each_figure(sgf, options)?;
+    Ok(figures
+        .into_iter()
+        .enumerate()
+        .map(|(i, figure)| {
+            let path = dir.join(name_fn(i));
+            write_figure(&figure?, &path)?;
+            Ok(path)
+        })
+        .collect())
+}
+
+fn write_figure(figure: &Element, path: &Path) -> Result<(), MakeSvgError> {
+    let file_error = |error| MakeSvgError::FileError(path.to_path_buf(), error);
+    let mut writer = BufWriter::new(File::create(path).map_err(file_error)?);
+    figure
+        .write_to(&mut writer)
+        .map_err(MakeSvgError::WriteError)?;
+    writer.flush().map_err(file_error)
+}
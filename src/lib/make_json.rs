@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::errors::MakeSvgError;
+use crate::goban::{Goban, Stone};
+use crate::make_svg::{get_move_numbers, MakeSvgOptions};
+use crate::parse::parse_go;
+
+type Point = (u8, u8);
+
+#[derive(Debug, Serialize)]
+struct GobanDescription {
+    size: Point,
+    x_range: Range<u8>,
+    y_range: Range<u8>,
+    stones: Vec<Stone>,
+    move_numbers: Vec<MoveNumber>,
+    last_move: Option<Point>,
+    ko: Option<Point>,
+    marks: Vec<Point>,
+    triangles: Vec<Point>,
+    circles: Vec<Point>,
+    squares: Vec<Point>,
+    selected: Vec<Point>,
+    dimmed: Vec<Point>,
+    labels: Vec<Label>,
+    lines: Vec<(Point, Point)>,
+    arrows: Vec<(Point, Point)>,
+    black_territory: Vec<Point>,
+    white_territory: Vec<Point>,
+    comment: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MoveNumber {
+    number: u64,
+    x: u8,
+    y: u8,
+}
+
+#[derive(Debug, Serialize)]
+struct Label {
+    x: u8,
+    y: u8,
+    text: String,
+}
+
+/// Describe the selected node as JSON instead of drawing it.
+///
+/// Includes the stones, markup, displayed move numbers and the range which would be drawn.
+/// Collections are sorted so output is deterministic.
+pub fn make_json(sgf: &str, options: &MakeSvgOptions) -> Result<String, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let goban = Goban::from_node_in_collection(
+        &options.node_description,
+        &collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    options.check_strict(&goban)?;
+    let (x_range, y_range) = options.goban_range.get_ranges(&goban, options)?;
+    let move_numbers = match &options.move_number_options {
+        Some(move_number_options) => get_move_numbers(&goban, options)
+            .into_iter()
+            .map(|(n, stone)| MoveNumber {
+                number: move_number_options.display_number(n),
+                x: stone.x,
+                y: stone.y,
+            })
+            .collect(),
+        None => vec![],
+    };
+    let mut stones: Vec<Stone> = goban.stones().collect();
+    stones.sort_by_key(|stone| (stone.y, stone.x));
+    let mut labels: Vec<Label> = goban
+        .labels()
+        .map(|(&(x, y), text)| Label {
+            x,
+            y,
+            text: text.clone(),
+        })
+        .collect();
+    labels.sort_by_key(|label| (label.x, label.y));
+    let description = GobanDescription {
+        size: goban.size(),
+        x_range,
+        y_range,
+        stones,
+        move_numbers,
+        last_move: goban.last_move(),
+        ko: goban.ko(),
+        marks: sorted(goban.marks()),
+        triangles: sorted(goban.triangles()),
+        circles: sorted(goban.circles()),
+        squares: sorted(goban.squares()),
+        selected: sorted(goban.selected()),
+        dimmed: sorted(goban.dimmed()),
+        labels,
+        lines: sorted(goban.lines()),
+        arrows: sorted(goban.arrows()),
+        black_territory: sorted(goban.black_territory()),
+        white_territory: sorted(goban.white_territory()),
+        comment: goban.comment().map(str::to_string),
+    };
+    serde_json::to_string(&description).map_err(|e| MakeSvgError::JsonError(e.into()))
+}
+
+fn sorted<T: Ord>(items: impl Iterator<Item = T>) -> Vec<T> {
+    let mut items: Vec<T> = items.collect();
+    items.sort_unstable();
+    items
+}
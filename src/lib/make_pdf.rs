@@ -0,0 +1,27 @@
+use minidom::Element;
+use svg2pdf::usvg::{self, fontdb, PostProcessingSteps, TreeParsing, TreePostProc};
+
+use crate::errors::MakeSvgError;
+use crate::make_png::FONT_DATA;
+use crate::make_svg::{make_svg, MakeSvgOptions};
+
+/// Render a single page PDF of the selected node.
+///
+/// The page is the size of the svg. Text is converted to vector outlines.
+pub fn make_pdf(sgf: &str, options: &MakeSvgOptions) -> Result<Vec<u8>, MakeSvgError> {
+    let svg = make_svg(sgf, options)?;
+    svg_to_pdf(&svg)
+}
+
+/// Convert an svg generated by `make_svg` to PDF bytes.
+pub fn svg_to_pdf(svg: &Element) -> Result<Vec<u8>, MakeSvgError> {
+    let mut buffer: Vec<u8> = vec![];
+    svg.write_to(&mut buffer)
+        .map_err(|e| MakeSvgError::PdfError(e.into()))?;
+    let mut tree = usvg::Tree::from_data(&buffer, &usvg::Options::default())
+        .map_err(|e| MakeSvgError::PdfError(e.into()))?;
+    let mut fontdb = fontdb::Database::new();
+    fontdb.load_font_data(FONT_DATA.to_vec());
+    tree.postprocess(PostProcessingSteps::default(), &fontdb);
+    Ok(svg2pdf::convert_tree(&tree, svg2pdf::Options::default()))
+}
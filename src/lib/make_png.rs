@@ -0,0 +1,52 @@
+use minidom::Element;
+
+use crate::errors::MakeSvgError;
+use crate::make_svg::{make_svg, MakeSvgOptions};
+
+/// The resolution at which one svg unit maps to one pixel.
+pub static BASE_DPI: f64 = 96.0;
+
+pub(crate) static FONT_DATA: &[u8] = include_bytes!("../../resources/Inter-Bold.ttf");
+
+/// Render a PNG of the selected node.
+///
+/// The width `options.size` gives is treated as the logical width at `BASE_DPI`, and the pixel
+/// dimensions scale with `dpi`.
+pub fn make_png(sgf: &str, options: &MakeSvgOptions, dpi: f64) -> Result<Vec<u8>, MakeSvgError> {
+    let svg = make_svg(sgf, options)?;
+    svg_to_png(&svg, dpi)
+}
+
+/// Rasterize an svg generated by `make_svg` to PNG bytes.
+pub fn svg_to_png(svg: &Element, dpi: f64) -> Result<Vec<u8>, MakeSvgError> {
+    rasterize(svg, dpi)?
+        .encode_png()
+        .map_err(|e| MakeSvgError::PngError(e.into()))
+}
+
+/// Rasterize an svg generated by `make_svg` to a pixmap.
+pub(crate) fn rasterize(svg: &Element, dpi: f64) -> Result<tiny_skia::Pixmap, MakeSvgError> {
+    let tree = {
+        let mut buffer: Vec<u8> = vec![];
+        svg.write_to(&mut buffer)
+            .map_err(|e| MakeSvgError::PngError(e.into()))?;
+        let mut fontdb = usvg::fontdb::Database::new();
+        fontdb.load_font_data(FONT_DATA.to_vec());
+        usvg::Tree::from_data(&buffer, &usvg::Options::default(), &fontdb)
+            .map_err(|e| MakeSvgError::PngError(e.into()))?
+    };
+    let scale = (dpi / BASE_DPI) as f32;
+    let pixmap_size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or(MakeSvgError::PngError("Invalid image size".into()))?;
+    let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height())
+        .ok_or(MakeSvgError::PngError("Invalid image size".into()))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap)
+}
@@ -1,42 +1,235 @@
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::ops::Range;
 
-use minidom::Element;
+use minidom::{Element, ElementBuilder};
+use sgf_parse::{go, SgfNode};
 
 use crate::board_side::{BoardSide, BoardSideSet};
+use crate::board_transform::BoardTransform;
+use crate::coordinates::CoordinateStyle;
 use crate::errors::MakeSvgError;
+use crate::game_info::{GameInfo, ResultFormat};
 use crate::goban::{Goban, Stone, StoneColor};
 use crate::goban_range::GobanRange;
-use crate::goban_style::GobanStyle;
-use crate::node_description::NodeDescription;
+use crate::goban_style::{default_hoshi_radius, parse_rgb, GobanStyle};
+use crate::node_description::{NodeDescription, NodePathStep};
+use crate::parse::parse_go;
 
 pub static NAMESPACE: &str = "http://www.w3.org/2000/svg";
 
-static BOARD_MARGIN: f64 = 0.64;
 static LABEL_MARGIN: f64 = 0.8;
 static REPEATED_MOVES_MARGIN: f64 = 0.32;
+static CAPTION_MARGIN: f64 = 0.32;
+static CAPTION_LINE_HEIGHT: f64 = 1.3;
+static LEGEND_ROW_HEIGHT: f64 = 0.8;
+static COMMENT_LIST_WIDTH: f64 = 8.0;
+static GHOST_STONE_OPACITY: f64 = 0.3;
+static DEAD_STONE_OPACITY: f64 = 0.4;
+static POINT_MARKER_OPACITY: f64 = 0.4;
+static MOVE_TRAIL_OPACITY: f64 = 0.5;
+/// Opacity of the patches behind numbers and labels on textured boards, letting the grain
+/// through.
+static TEXTURED_PATCH_OPACITY: f64 = 0.7;
+/// Colors for `colorize_labels`, dark enough to read on white stones and the board.
+static LABEL_PALETTE: [&str; 8] = [
+    "#d62728", "#1f77b4", "#2ca02c", "#9467bd", "#ff7f0e", "#8c564b", "#e377c2", "#17becf",
+];
+/// Colors of solution marks, fixed so they read the same in every style.
+static CORRECT_COLOR: &str = "#2e9e44";
+static WRONG_COLOR: &str = "#d32f2f";
+static SOLUTION_STROKE_WIDTH: f64 = 0.12;
+/// Approximate width of a character relative to the font size.
+static CHARACTER_WIDTH: f64 = 0.6;
 
-static FONT_FAMILY: &str = "Inter";
-static FONT_SIZE: f64 = 0.45;
-static FONT_WEIGHT: usize = 700;
+/// Ranges of board columns and rows.
+type BoardRanges = (Range<u8>, Range<u8>);
+
+/// Builds the group for one kind of point markup, leaving out the given points.
+type MarkupGroupBuilder = fn(&Goban, &MakeSvgOptions, &HashSet<(u8, u8)>) -> Element;
 
 #[derive(Debug, Clone)]
 pub struct MakeSvgOptions {
     pub node_description: NodeDescription,
+    pub game_index: usize,
+    /// Fail with `PointOffBoard` if the SGF places stones or markup outside the board,
+    /// instead of leaving them out.
+    pub strict: bool,
     pub goban_range: GobanRange,
+    pub transform: BoardTransform,
+    /// Further rotate the board clockwise by this many quarter turns (0 to 3) after `transform`.
+    pub rotate_quarter_turns: u8,
     pub style: GobanStyle,
-    pub viewbox_width: f64,
+    pub size: SizeSpec,
+    /// The diagram shrinks to fit this height if it's set, whatever `size` says.
+    pub viewbox_height: Option<f64>,
+    /// Below this diagram width, e.g. for thumbnails, draw only the board and stones,
+    /// leaving out move numbers, markup, coordinates and captions whatever else is set.
+    pub simplify_below_width: Option<f64>,
+    pub board_margin: f64,
+    /// Extra space around the drawn range where stones and markup are still drawn.
+    pub clip_margin: f64,
+    /// Blank space around the whole diagram, in board units. Unlike `board_margin` it's outside
+    /// the labels and captions, and it widens the viewbox rather than shrinking the board.
+    pub padding: f64,
+    pub font_family: String,
+    pub font_size: f64,
+    pub font_weight: u32,
+    /// Leave out the background so the diagram can be overlaid on other content.
+    pub transparent_background: bool,
+    /// Radius of stones relative to the line spacing, in (0, 0.5].
+    pub stone_radius: f64,
+    pub stone_variation: bool,
+    /// Mask the board lines so they don't show under (semi-transparent) stones.
+    pub clip_lines_under_stones: bool,
+    pub stone_opacity: f64,
+    pub dim_setup_stones: bool,
+    /// Draw black stones faintly, e.g. to show only white's shape in a problem.
+    pub ghost_black: bool,
+    /// Draw white stones faintly, e.g. to show only black's shape in a problem.
+    pub ghost_white: bool,
+    pub perspective_tilt: Option<f64>,
+    /// Direction of the light on stones drawn with radial gradients, in degrees
+    /// counterclockwise from the right. The style's own highlight is kept if unset.
+    pub light_angle: Option<f64>,
+    pub shape_rendering: ShapeRendering,
+    /// Adjust the width so each board cell is a whole number of units, and so of pixels at
+    /// `BASE_DPI`, keeping stones and lines sharp in raster output. Ignored with a fixed height.
+    pub snap_to_pixel_grid: bool,
+    /// Fill the background with a texture instead of the style's background color.
+    pub board_texture: Option<BoardTexture>,
+    /// Fade the board out past the last line on cropped sides of the range.
+    pub fade_crop_edges: bool,
+    /// Shape of the arrowheads on arrows.
+    pub arrowhead_style: ArrowStyle,
+    /// Shape of the marks at both ends of lines.
+    pub linehead_style: ArrowStyle,
+    /// Hoshi to draw instead of the standard ones for the board size, if not empty.
+    pub hoshi_points: Vec<(u8, u8)>,
+    /// On boards with more lines than this in either direction, thin the grid lines in
+    /// proportion, so oversized boards stay light.
+    pub line_thinning_above: Option<u8>,
     pub label_sides: BoardSideSet,
+    pub coordinate_style: CoordinateStyle,
+    /// Skip 'I' in lettered column labels, as in Western convention.
+    pub skip_i_column: bool,
+    /// The point to label as the first column and row (e.g. A1), so a cropped region can be
+    /// presented as a board of its own. Points before it go unlabelled.
+    pub label_origin: Option<(u8, u8)>,
     pub move_number_options: Option<MoveNumberOptions>,
+    /// Number each diagram from `make_svg_sequence` or `make_figures` from 1, counting only the
+    /// moves since the previous diagram.
+    pub reset_numbering_per_segment: bool,
+    /// Don't draw markup on stones, so stones show only their move numbers.
+    pub numbered_stones_only: bool,
+    pub move_number_halo: bool,
+    /// Tint move numbers by parity, so odd (Black's) and even (White's) moves can be told
+    /// apart whatever stone they're on.
+    pub parity_tint: bool,
+    /// Connect the numbered moves still on the board in order with faint lines. Nothing is
+    /// drawn without `move_number_options`.
+    pub draw_move_trail: bool,
+    /// Shape behind move numbers on empty points.
+    pub move_number_background: BackgroundShape,
+    pub move_numbers_on_top: bool,
+    /// List every move number at points played more than once instead of just the last one.
+    /// Ignored in kifu mode, which lists replayed moves separately.
+    pub show_all_numbers_at_point: bool,
+    /// Fill colors for move numbers in the given ranges of moves. The first match is used.
+    pub move_number_color_ranges: Vec<(Range<u64>, String)>,
+    /// Only number these moves, leaving other stones plain.
+    pub number_only_moves: Option<Vec<u64>>,
     pub draw_marks: bool,
     pub draw_triangles: bool,
     pub draw_circles: bool,
     pub draw_squares: bool,
     pub draw_selected: bool,
+    /// Kinds of markup to draw beneath the stones rather than over them, e.g. for shading
+    /// regions with squares.
+    pub background_markup: Vec<MarkupKind>,
     pub draw_dimmed: bool,
     pub draw_labels: bool,
+    /// Characters of each label to draw. Labels longer than 2 characters are shrunk to fit.
+    pub max_label_chars: usize,
+    /// End labels cut short by `max_label_chars` with an ellipsis.
+    pub label_ellipsis: bool,
+    /// Draw each distinct label text in a color from a fixed palette, chosen by hashing the
+    /// text so it's the same in every diagram.
+    pub colorize_labels: bool,
     pub draw_lines: bool,
     pub draw_arrows: bool,
+    /// Dash pattern for SGF lines and arrows, in units of one board cell.
+    pub line_dash: Option<Vec<f64>>,
+    /// Leave out lines and arrows with an end outside the drawn range, instead of cutting them
+    /// off at its edge.
+    pub trim_offboard_annotations: bool,
+    pub embed_point_titles: bool,
+    /// Give each move's stone and number an id and a `data-move` attribute, so they can be
+    /// revealed one at a time with CSS or scripts.
+    pub layer_moves: bool,
+    /// Tag stones, board lines and markup with `class` attributes instead of inline colors, so
+    /// they can be styled by a stylesheet.
+    pub emit_css_classes: bool,
+    /// Set colors and stroke widths as attributes on each element. Otherwise those repeated
+    /// across elements are moved into classes defined in a `<style>` element.
+    pub inline_styles: bool,
+    /// Round coordinates in attributes to this many decimal places. Numbers are otherwise
+    /// written with up to 4. Sizes, opacities and the diagram's scale are left alone.
+    pub coordinate_precision: Option<u8>,
+    /// Stones to annotate with the number of liberties of their group.
+    pub annotate_liberties: Vec<(u8, u8)>,
+    /// Points to circle with a ring, independent of any SGF markup.
+    pub highlight_moves: Vec<(u8, u8)>,
+    /// Stones whose whole group of connected stones is outlined.
+    pub outline_groups: Vec<(u8, u8)>,
+    /// Stones to draw faded as dead. Territory markers are drawn over them.
+    pub dead_stones: Vec<(u8, u8)>,
+    /// Fills for particular stones, e.g. to pick out a weak group, used instead of the style's
+    /// stone fill. Stones keep their outline.
+    pub stone_overrides: HashMap<(u8, u8), String>,
+    /// Points to mark as a correct or wrong answer to a problem, drawn over the stones.
+    pub solution_marks: Vec<((u8, u8), SolutionKind)>,
+    /// Shade points by a value from -1 (favoring black) to 1 (favoring white), e.g. from
+    /// engine ownership estimates. Values outside that range are clamped.
+    pub influence: HashMap<(u8, u8), f64>,
+    /// `#rgb`, `#rrggbb`, `black` or `white` colors for the black and white ends of the
+    /// influence scale.
+    pub influence_colors: (String, String),
+    pub draw_territory: bool,
+    /// Mark every empty point in the drawn range with a faint dot.
+    pub draw_all_points: bool,
+    /// Draw the board's grid lines. With these and `draw_hoshi` off and
+    /// `transparent_background` on, only stones and markup are drawn, for overlaying on a
+    /// picture of a real board.
+    pub draw_grid_lines: bool,
+    pub draw_hoshi: bool,
+    pub draw_last_move: bool,
+    pub draw_ko: bool,
+    /// Mark the points where stones were captured by the current move.
+    pub draw_capture_markers: bool,
+    pub draw_comment: bool,
+    /// List the comments on moves up to the selected node in a column beside the board,
+    /// widening the diagram to fit.
+    pub draw_comment_list: bool,
+    pub draw_passes: bool,
+    /// Draw a key below the diagram for the kinds of markup shown.
+    pub draw_legend: bool,
+    /// Legend text for each kind of markup. Kinds without a label use their name.
+    pub legend_labels: HashMap<MarkupKind, String>,
+    pub draw_title: bool,
+    pub draw_captures: bool,
+    /// Stamp the game result on the board when the selected node ends the game.
+    pub draw_result_stamp: bool,
+    pub result_stamp_corner: BoardCorner,
+    /// Draw a box with the komi, handicap, ruleset and board size on the board.
+    pub draw_info_box: bool,
+    pub info_box_corner: BoardCorner,
+    /// How the result is shown in the title and result stamp.
+    pub result_format: ResultFormat,
+    /// Text for a `<desc>` element describing the diagram for screen readers.
+    pub accessible_description: Option<String>,
+    /// Add `<title>` and `<desc>` elements, generating a description if none is given.
+    pub generate_a11y: bool,
     pub kifu_mode: bool,
 }
 
@@ -45,74 +238,893 @@ pub struct MoveNumberOptions {
     pub start: u64,
     pub end: Option<u64>,
     pub count_from: u64,
+    pub display: MoveNumberDisplay,
+    /// Wrap displayed numbers to the range `1..=modulo`.
+    pub modulo: Option<u64>,
+}
+
+impl MoveNumberOptions {
+    /// The number to display for the `n`th move.
+    pub fn display_number(&self, n: u64) -> u64 {
+        let number = match self.display {
+            MoveNumberDisplay::Relative => n + self.count_from - self.start,
+            MoveNumberDisplay::Absolute => n,
+        };
+        match self.modulo {
+            Some(modulo) if number > 0 => (number - 1) % modulo + 1,
+            _ => number,
+        }
+    }
+}
+
+/// How move numbers are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MoveNumberDisplay {
+    /// Count from the first numbered move (see `count_from`).
+    Relative,
+    /// Show the actual move number.
+    Absolute,
+}
+
+/// How the size of the diagram is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeSpec {
+    /// Width of the whole diagram.
+    TotalWidth(f64),
+    /// Width of a board cell. The diagram's width follows from it and the drawn range, so
+    /// boards of different sizes share a scale.
+    CellSize(f64),
+}
+
+/// A corner of the drawn area of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BoardCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Whether a solution mark shows a correct or wrong answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionKind {
+    /// A green check.
+    Correct,
+    /// A red cross.
+    Wrong,
+}
+
+/// A kind of SGF markup which can appear in the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum MarkupKind {
+    Mark,
+    Triangle,
+    Circle,
+    Square,
+    Selected,
+}
+
+impl MarkupKind {
+    const ALL: [MarkupKind; 5] = [
+        MarkupKind::Mark,
+        MarkupKind::Triangle,
+        MarkupKind::Circle,
+        MarkupKind::Square,
+        MarkupKind::Selected,
+    ];
+
+    fn default_label(&self) -> &'static str {
+        match self {
+            MarkupKind::Mark => "Mark",
+            MarkupKind::Triangle => "Triangle",
+            MarkupKind::Circle => "Circle",
+            MarkupKind::Square => "Square",
+            MarkupKind::Selected => "Selected",
+        }
+    }
+
+    /// Whether this kind of markup is drawn on `goban`.
+    fn is_shown(&self, goban: &Goban, options: &MakeSvgOptions) -> bool {
+        match self {
+            MarkupKind::Mark => options.draw_marks && goban.marks().next().is_some(),
+            MarkupKind::Triangle => options.draw_triangles && goban.triangles().next().is_some(),
+            MarkupKind::Circle => options.draw_circles && goban.circles().next().is_some(),
+            MarkupKind::Square => options.draw_squares && goban.squares().next().is_some(),
+            MarkupKind::Selected => options.draw_selected && goban.selected().next().is_some(),
+        }
+    }
+
+    /// Draw the symbol for this kind of markup, centered on the origin.
+    fn draw_symbol(&self, style: &GobanStyle) -> Element {
+        match self {
+            MarkupKind::Mark => draw_mark(0, 0, None, style),
+            MarkupKind::Triangle => draw_triangle(0, 0, None, style),
+            MarkupKind::Circle => draw_circle(0, 0, None, style),
+            MarkupKind::Square => draw_square(0, 0, None, style),
+            MarkupKind::Selected => draw_selected(0, 0, None, style),
+        }
+    }
+}
+
+/// Antialiasing hint for renderers, set as `shape-rendering` on the whole diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ShapeRendering {
+    #[default]
+    Auto,
+    /// Turn off antialiasing for sharp edges in small raster images.
+    CrispEdges,
+    /// Favour accurate geometry over speed.
+    GeometricPrecision,
+}
+
+impl ShapeRendering {
+    fn attr_value(&self) -> Option<&'static str> {
+        match self {
+            ShapeRendering::Auto => None,
+            ShapeRendering::CrispEdges => Some("crispEdges"),
+            ShapeRendering::GeometricPrecision => Some("geometricPrecision"),
+        }
+    }
+}
+
+/// The shape drawn behind move numbers on empty points to hide the board lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum BackgroundShape {
+    #[default]
+    Rectangle,
+    Circle,
+    /// Leave the lines showing behind the number.
+    None,
+}
+
+/// A texture to fill the background with instead of the style's flat color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BoardTexture {
+    /// Light wood with subtle grain.
+    Wood,
+    /// Off-white paper, slightly darker towards the edges.
+    Paper,
+}
+
+impl BoardTexture {
+    /// A plain color close to the texture's average, for patches over it.
+    fn base_color(&self) -> &'static str {
+        match self {
+            BoardTexture::Wood => "#ddb165",
+            BoardTexture::Paper => "#f4eee1",
+        }
+    }
+
+    /// Gradient definition for the texture, with id `board-texture`.
+    fn definition(&self) -> Element {
+        let (builder, stops): (_, &[(&str, &str)]) = match self {
+            BoardTexture::Wood => (
+                // Reflected bands at a slight angle look like grain.
+                Element::builder("linearGradient", NAMESPACE)
+                    .attr("x1", "0")
+                    .attr("y1", "0")
+                    .attr("x2", "0.25")
+                    .attr("y2", "0.05")
+                    .attr("spreadMethod", "reflect"),
+                &[
+                    ("0", "#e6bd73"),
+                    ("0.3", "#dcb064"),
+                    ("0.45", "#e3b76c"),
+                    ("0.7", "#d4a659"),
+                    ("1", "#e0b46a"),
+                ],
+            ),
+            BoardTexture::Paper => (
+                Element::builder("radialGradient", NAMESPACE)
+                    .attr("cx", "0.5")
+                    .attr("cy", "0.5")
+                    .attr("r", "0.75"),
+                &[("0", "#fbf8f0"), ("1", "#ece5d3")],
+            ),
+        };
+        builder
+            .attr("id", "board-texture")
+            .append_all(stops.iter().map(|(offset, color)| {
+                Element::builder("stop", NAMESPACE)
+                    .attr("offset", *offset)
+                    .attr("stop-color", *color)
+                    .build()
+            }))
+            .build()
+    }
+}
+
+/// The shape drawn at the ends of lines and arrows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ArrowStyle {
+    /// A filled arrowhead.
+    Triangle,
+    /// An unfilled arrowhead.
+    Open,
+    /// A filled circle.
+    Dot,
+    /// A filled diamond.
+    Diamond,
+}
+
+impl ArrowStyle {
+    /// Marker definition for the shape with the given id.
+    ///
+    /// Marker sizes use the default `markerUnits="strokeWidth"`, so line ends scale with the
+    /// stroke width of the lines and arrows they're drawn on. They're drawn in `color`, which
+    /// should match the markup stroke.
+    fn marker(&self, id: &str, orient: &str, color: &str) -> Element {
+        let (width, height, ref_x, shape) = match self {
+            ArrowStyle::Triangle => (
+                7,
+                5,
+                "7",
+                Element::builder("polygon", NAMESPACE)
+                    .attr("points", "0 0, 7 2.5, 0 5")
+                    .attr("fill", color),
+            ),
+            ArrowStyle::Open => (
+                7,
+                5,
+                "6.5",
+                Element::builder("polyline", NAMESPACE)
+                    .attr("points", "0.5 0.5, 6.5 2.5, 0.5 4.5")
+                    .attr("fill", "none")
+                    .attr("stroke", color)
+                    .attr("stroke-width", "1"),
+            ),
+            ArrowStyle::Dot => (
+                4,
+                4,
+                "2",
+                Element::builder("circle", NAMESPACE)
+                    .attr("cx", "2")
+                    .attr("cy", "2")
+                    .attr("r", "2")
+                    .attr("fill", color),
+            ),
+            ArrowStyle::Diamond => (
+                6,
+                4,
+                "3",
+                Element::builder("polygon", NAMESPACE)
+                    .attr("points", "0 2, 3 0, 6 2, 3 4")
+                    .attr("fill", color),
+            ),
+        };
+        Element::builder("marker", NAMESPACE)
+            .attr("id", id)
+            .attr("markerWidth", width.to_string())
+            .attr("markerHeight", height.to_string())
+            .attr("refX", ref_x)
+            .attr("refY", format_float(f64::from(height) / 2.0))
+            // Dots look the same whichever way they face.
+            .attr("orient", (*self != ArrowStyle::Dot).then_some(orient))
+            .append(shape)
+            .build()
+    }
+}
+
+impl MakeSvgOptions {
+    /// Check for option values which can't produce a sensible diagram.
+    ///
+    /// The command line rejects most of these when parsing arguments, but options built in code
+    /// aren't checked until rendering.
+    pub fn validate(&self) -> Result<(), MakeSvgError> {
+        let invalid = |reason: &str| Err(MakeSvgError::InvalidOptions(reason.to_string()));
+        if self.rotate_quarter_turns > 3 {
+            return invalid("rotate_quarter_turns must be between 0 and 3");
+        }
+        match self.size {
+            SizeSpec::TotalWidth(width) if !(width.is_finite() && width > 0.0) => {
+                return invalid("viewbox width must be positive");
+            }
+            SizeSpec::CellSize(cell_size) if !(cell_size.is_finite() && cell_size > 0.0) => {
+                return invalid("cell size must be positive");
+            }
+            _ => {}
+        }
+        if let Some(height) = self.viewbox_height {
+            if !(height.is_finite() && height > 0.0) {
+                return invalid("viewbox_height must be positive");
+            }
+        }
+        if !(self.font_size.is_finite() && self.font_size > 0.0) {
+            return invalid("font_size must be positive");
+        }
+        if !(self.board_margin.is_finite() && self.board_margin >= 0.0) {
+            return invalid("board_margin must not be negative");
+        }
+        if !(self.clip_margin.is_finite() && self.clip_margin >= 0.0) {
+            return invalid("clip_margin must not be negative");
+        }
+        if self.line_thinning_above == Some(0) {
+            return invalid("line_thinning_above must be positive");
+        }
+        if self.max_label_chars == 0 {
+            return invalid("max_label_chars must be positive");
+        }
+        if !(self.padding.is_finite() && self.padding >= 0.0) {
+            return invalid("padding must not be negative");
+        }
+        if !(self.stone_radius > 0.0 && self.stone_radius <= 0.5) {
+            return invalid("stone_radius must be greater than 0 and at most 0.5");
+        }
+        if !(0.0..=1.0).contains(&self.stone_opacity) {
+            return invalid("stone_opacity must be between 0 and 1");
+        }
+        if let Some(tilt) = self.perspective_tilt {
+            if !(0.0..90.0).contains(&tilt) {
+                return invalid("perspective_tilt must be at least 0 and less than 90 degrees");
+            }
+        }
+        if self.light_angle.is_some_and(|angle| !angle.is_finite()) {
+            return invalid("light_angle must be finite");
+        }
+        if let Some(dashes) = &self.line_dash {
+            if dashes.iter().any(|&dash| dash < 0.0) {
+                return invalid("line_dash lengths must not be negative");
+            }
+        }
+        if let GobanRange::Ranged(x_range, y_range) = &self.goban_range {
+            if x_range.is_empty() || y_range.is_empty() {
+                return invalid("goban_range must include at least one column and row");
+            }
+        }
+        if parse_rgb(&self.influence_colors.0).is_none()
+            || parse_rgb(&self.influence_colors.1).is_none()
+        {
+            return invalid("influence_colors must be #rgb, #rrggbb, black or white");
+        }
+        if let Some(move_number_options) = &self.move_number_options {
+            if move_number_options.start == 0 {
+                return invalid("move numbering must start at move 1 or later");
+            }
+            if let Some(end) = move_number_options.end {
+                if end < move_number_options.start {
+                    return invalid("move numbering must not end before it starts");
+                }
+            }
+            if move_number_options.modulo == Some(0) {
+                return invalid("move number modulo must be positive");
+            }
+        }
+        Ok(())
+    }
+
+    /// These options with everything but the board and stones turned off, if the diagram is
+    /// narrower than `simplify_below_width`.
+    ///
+    /// With a `SizeSpec::CellSize` the width compared is the board's, without coordinate labels
+    /// or padding, since those are among what simplifying removes.
+    fn simplified(&self, goban: &Goban) -> Result<Option<MakeSvgOptions>, MakeSvgError> {
+        let threshold = match self.simplify_below_width {
+            Some(threshold) => threshold,
+            None => return Ok(None),
+        };
+        let width = match self.size {
+            SizeSpec::TotalWidth(width) => width,
+            SizeSpec::CellSize(cell_size) => {
+                let (x_range, _) = self.goban_range.get_ranges(goban, self)?;
+                let columns = f64::from(x_range.end - x_range.start);
+                cell_size * (columns - 1.0 + 2.0 * self.board_margin)
+            }
+        };
+        if width >= threshold {
+            return Ok(None);
+        }
+        Ok(Some(MakeSvgOptions {
+            label_sides: BoardSideSet::default(),
+            move_number_options: None,
+            draw_move_trail: false,
+            draw_marks: false,
+            draw_triangles: false,
+            draw_circles: false,
+            draw_squares: false,
+            draw_selected: false,
+            draw_dimmed: false,
+            draw_labels: false,
+            draw_lines: false,
+            draw_arrows: false,
+            annotate_liberties: Vec::new(),
+            highlight_moves: Vec::new(),
+            outline_groups: Vec::new(),
+            solution_marks: Vec::new(),
+            draw_territory: false,
+            draw_last_move: false,
+            draw_ko: false,
+            draw_capture_markers: false,
+            draw_comment: false,
+            draw_comment_list: false,
+            draw_passes: false,
+            draw_legend: false,
+            draw_title: false,
+            draw_captures: false,
+            draw_result_stamp: false,
+            draw_info_box: false,
+            draw_all_points: false,
+            kifu_mode: false,
+            embed_point_titles: false,
+            influence: HashMap::new(),
+            dead_stones: Vec::new(),
+            stone_overrides: HashMap::new(),
+            ..self.clone()
+        }))
+    }
+
+    /// Fail with `PointOffBoard` if `strict` is set and anything in the SGF was left off
+    /// `goban`. Every renderer calls this once it has the board.
+    pub(crate) fn check_strict(&self, goban: &Goban) -> Result<(), MakeSvgError> {
+        match goban.dropped_points().first() {
+            Some(&point) if self.strict => Err(MakeSvgError::PointOffBoard(point)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The transform to apply to the board, including any quarter turns.
+    pub fn board_transform(&self) -> BoardTransform {
+        self.transform
+            .then(BoardTransform::quarter_turns(self.rotate_quarter_turns))
+    }
+
+    /// The width and height of the viewbox `make_svg` would produce for `sgf`.
+    ///
+    /// The diagram is laid out as for rendering, since captions and other text affect its
+    /// height, but isn't assembled into a document. Laying it out applies the same checks,
+    /// `strict` included, so this fails for any input `make_svg` rejects.
+    pub fn output_dimensions(&self, sgf: &str) -> Result<(f64, f64), MakeSvgError> {
+        self.validate()?;
+        let collection = parse_go(sgf)?;
+        let goban = Goban::from_node_in_collection(
+            &self.node_description,
+            &collection,
+            self.game_index,
+            self.board_transform(),
+        )?;
+        let layout = layout_goban(&goban, self)?;
+        Ok((layout.viewbox_width, layout.viewbox_height))
+    }
 }
 
 pub fn make_svg(sgf: &str, options: &MakeSvgOptions) -> Result<Element, MakeSvgError> {
-    let collection = sgf_parse::go::parse(sgf)?;
-    let goban = Goban::from_node_in_collection(&options.node_description, &collection)?;
-    let (x_range, y_range) = options.goban_range.get_ranges(&goban, options)?;
+    make_svg_from_collection(&parse_go(sgf)?, options)
+}
+
+/// Render a collection already parsed with `sgf_parse::go::parse`, for callers which share one
+/// parse between several uses.
+pub fn make_svg_from_collection(
+    collection: &[SgfNode<go::Prop>],
+    options: &MakeSvgOptions,
+) -> Result<Element, MakeSvgError> {
+    options.validate()?;
+    let goban = Goban::from_node_in_collection(
+        &options.node_description,
+        collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    render_goban(&goban, options)
+}
+
+/// Render the svg with each move's stone and number individually addressable, for revealing
+/// the moves one at a time, e.g. in a presentation.
+///
+/// The stone played at move `n` gets the id `move-n-stone` and its number `move-n-number`, and
+/// both have `data-move="n"`. This is `make_svg` with `options.layer_moves` set.
+pub fn make_layered_svg(sgf: &str, options: &MakeSvgOptions) -> Result<Element, MakeSvgError> {
+    let options = MakeSvgOptions {
+        layer_moves: true,
+        ..options.clone()
+    };
+    make_svg(sgf, &options)
+}
+
+/// Render the svg and write it directly to `writer`.
+pub fn make_svg_to_writer<W: std::io::Write>(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    writer: &mut W,
+) -> Result<(), MakeSvgError> {
+    let svg = make_svg(sgf, options)?;
+    svg.write_to(writer).map_err(MakeSvgError::WriteError)
+}
+
+/// Render the nodes at each of `node_numbers` along the main variation.
+///
+/// The sgf is only parsed once. `options.node_description` is ignored. With
+/// `options.reset_numbering_per_segment`, each diagram only numbers the moves since the previous
+/// one, starting from 1.
+pub fn make_svg_sequence(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    node_numbers: &[u64],
+) -> Result<Vec<Element>, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let mut previous_move_number = 0;
+    node_numbers
+        .iter()
+        .map(|&n| {
+            let node_description = NodeDescription {
+                steps: vec![NodePathStep::Advance(n as usize)],
+            };
+            let goban = Goban::from_node_in_collection(
+                &node_description,
+                &collection,
+                options.game_index,
+                options.board_transform(),
+            )?;
+            let first_move = previous_move_number + 1;
+            previous_move_number = goban.move_number();
+            match options.move_number_options {
+                Some(move_number_options) if options.reset_numbering_per_segment => {
+                    let options = MakeSvgOptions {
+                        move_number_options: Some(segment_move_number_options(
+                            move_number_options,
+                            first_move,
+                            goban.move_number(),
+                            options,
+                        )),
+                        ..options.clone()
+                    };
+                    render_goban(&goban, &options)
+                }
+                _ => render_goban(&goban, options),
+            }
+        })
+        .collect()
+}
+
+/// Render one diagram per figure (`FG` property) along the main variation.
+///
+/// Each figure runs from a node with `FG` up to the node before the next one, and is drawn at
+/// its last node with only the figure's moves numbered. Earlier moves appear as plain stones.
+/// Nodes before the first `FG` form a figure of their own. The numbering style is taken from
+/// `options.move_number_options` if set, otherwise absolute move numbers are shown, unless
+/// `options.reset_numbering_per_segment` is set to count each figure's moves from 1.
+/// `options.node_description` is ignored.
+pub fn make_figures(sgf: &str, options: &MakeSvgOptions) -> Result<Vec<Element>, MakeSvgError> {
+    render_each_figure(sgf, options)?.into_iter().collect()
+}
+
+/// Render the figures `make_figures` would, each on its own, so that one figure failing to
+/// render doesn't lose the others. Errors parsing `sgf` or finding the game still fail the
+/// whole call.
+pub(crate) fn render_each_figure(
+    sgf: &str,
+    options: &MakeSvgOptions,
+) -> Result<Vec<Result<Element, MakeSvgError>>, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let root = collection
+        .get(options.game_index)
+        .ok_or(MakeSvgError::MissingGame)?;
+    let node_count = root.main_variation().count();
+    let mut figure_starts: Vec<usize> = vec![0];
+    figure_starts.extend(
+        root.main_variation()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, node)| node.get_property("FG").is_some())
+            .map(|(i, _)| i),
+    );
+    let base_move_number_options = options.move_number_options.unwrap_or(MoveNumberOptions {
+        start: 1,
+        end: None,
+        count_from: 1,
+        display: MoveNumberDisplay::Absolute,
+        modulo: None,
+    });
+    let goban_at = |n: usize| {
+        let node_description = NodeDescription {
+            steps: vec![NodePathStep::Advance(n)],
+        };
+        Goban::from_node_in_collection(
+            &node_description,
+            &collection,
+            options.game_index,
+            options.board_transform(),
+        )
+    };
+    let render_figure = |i: usize, start: usize| {
+        let end = figure_starts
+            .get(i + 1)
+            .map(|next| next - 1)
+            .unwrap_or(node_count - 1);
+        let first_move = match start {
+            0 => 1,
+            _ => goban_at(start - 1)?.move_number() + 1,
+        };
+        let goban = goban_at(end)?;
+        let options = MakeSvgOptions {
+            move_number_options: Some(segment_move_number_options(
+                base_move_number_options,
+                first_move,
+                goban.move_number(),
+                options,
+            )),
+            ..options.clone()
+        };
+        render_goban(&goban, &options)
+    };
+    Ok(figure_starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| render_figure(i, start))
+        .collect())
+}
+
+/// Number the moves from `start` to `end` inclusive, counting from 1 if
+/// `options.reset_numbering_per_segment` is set.
+fn segment_move_number_options(
+    base: MoveNumberOptions,
+    start: u64,
+    end: u64,
+    options: &MakeSvgOptions,
+) -> MoveNumberOptions {
+    let mut move_number_options = MoveNumberOptions {
+        start,
+        end: Some(end),
+        ..base
+    };
+    if options.reset_numbering_per_segment {
+        move_number_options.display = MoveNumberDisplay::Relative;
+        move_number_options.count_from = 1;
+    }
+    move_number_options
+}
+
+/// Render only the region of the board which differs between two nodes.
+///
+/// The position at `to` is rendered, cropped to the bounding box of all intersections whose
+/// contents differ from the position at `from`. If nothing changed the full board is rendered.
+/// Returns the svg along with the rendered `(x_range, y_range)`.
+pub fn render_changed_region(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    from: &NodeDescription,
+    to: &NodeDescription,
+) -> Result<(Element, BoardRanges), MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let before = Goban::from_node_in_collection(
+        from,
+        &collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    let after = Goban::from_node_in_collection(
+        to,
+        &collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    let (width, height) = after.size();
+    let changed: Vec<(u8, u8)> = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .filter(|&(x, y)| before.stone_color(x, y) != after.stone_color(x, y))
+        .collect();
+    let goban_range = match (
+        changed.iter().map(|p| p.0).min(),
+        changed.iter().map(|p| p.0).max(),
+        changed.iter().map(|p| p.1).min(),
+        changed.iter().map(|p| p.1).max(),
+    ) {
+        (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) => {
+            GobanRange::Ranged(x_min..x_max + 1, y_min..y_max + 1)
+        }
+        _ => GobanRange::FullBoard,
+    };
+    let options = MakeSvgOptions {
+        goban_range,
+        ..options.clone()
+    };
+    let ranges = options.goban_range.get_ranges(&after, &options)?;
+    let svg = render_goban(&after, &options)?;
+    Ok((svg, ranges))
+}
+
+/// Render the diagram along with a table of the displayed move numbers.
+///
+/// Each entry pairs a displayed move number with its board coordinate (e.g. "Q16"), sorted by
+/// move number. The table is empty if move numbers aren't enabled.
+pub fn render_with_move_table(
+    sgf: &str,
+    options: &MakeSvgOptions,
+) -> Result<(Element, Vec<(u64, String)>), MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    let goban = Goban::from_node_in_collection(
+        &options.node_description,
+        &collection,
+        options.game_index,
+        options.board_transform(),
+    )?;
+    let svg = render_goban(&goban, options)?;
+    let table = match &options.move_number_options {
+        Some(move_number_options) => get_move_numbers(&goban, options)
+            .into_iter()
+            .map(|(n, stone)| {
+                (
+                    move_number_options.display_number(n),
+                    options.coordinate_style.point_label(
+                        stone.x,
+                        stone.y,
+                        goban.size(),
+                        options.skip_i_column,
+                    ),
+                )
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok((svg, table))
+}
+
+/// The parts of a rendered diagram, before they're assembled into an svg element.
+struct GobanLayout {
+    definitions: Element,
+    diagram: Element,
+    viewbox_width: f64,
+    viewbox_height: f64,
+}
+
+/// Lay out the diagram for `goban`, working out the size of the viewbox it's drawn in.
+fn layout_goban(goban: &Goban, options: &MakeSvgOptions) -> Result<GobanLayout, MakeSvgError> {
+    options.check_strict(goban)?;
+    let simplified = options.simplified(goban)?;
+    let options = simplified.as_ref().unwrap_or(options);
+    let (x_range, y_range) = options.goban_range.get_ranges(goban, options)?;
     let width = x_range.end - x_range.start;
     let height = y_range.end - y_range.start;
-    if !options.label_sides.is_empty() && width > 25 || height > 99 {
+    if !options.label_sides.is_empty()
+        && (width > options.coordinate_style.max_columns() || height > 99)
+    {
         return Err(MakeSvgError::UnlabellableRange);
     }
     let (top_margin, right_margin, bottom_margin, left_margin) = get_margins(&options.label_sides);
+    let fade_crop_edges = options.fade_crop_edges && (width, height) != goban.size();
 
     let definitions = {
         let clip_path = Element::builder("clipPath", NAMESPACE)
             .attr("id", "board-clip")
             .append(
                 Element::builder("rect", NAMESPACE)
-                    .attr("x", format_float(f64::from(x_range.start) - 0.5))
-                    .attr("y", format_float(f64::from(y_range.start) - 0.5))
-                    .attr("width", width.to_string())
-                    .attr("height", height.to_string())
+                    .attr(
+                        "x",
+                        format_float(f64::from(x_range.start) - 0.5 - options.clip_margin),
+                    )
+                    .attr(
+                        "y",
+                        format_float(f64::from(y_range.start) - 0.5 - options.clip_margin),
+                    )
+                    .attr(
+                        "width",
+                        format_float(f64::from(width) + 2.0 * options.clip_margin),
+                    )
+                    .attr(
+                        "height",
+                        format_float(f64::from(height) + 2.0 * options.clip_margin),
+                    )
                     .build(),
             )
             .build();
-        Element::builder("defs", NAMESPACE)
-            .append(clip_path)
-            .append_all(options.style.defs()?)
+        let mut defs_builder = Element::builder("defs", NAMESPACE).append(clip_path);
+        if options.clip_lines_under_stones {
+            defs_builder = defs_builder.append(build_stones_mask(goban, options));
+        }
+        if fade_crop_edges {
+            defs_builder = defs_builder.append_all(build_crop_fade_gradients(options));
+        }
+        if let Some(texture) = options.board_texture {
+            defs_builder = defs_builder.append(texture.definition());
+        }
+        defs_builder
+            // Lines have a marker at each end, so it's flipped at the start to face outwards.
+            .append(options.linehead_style.marker(
+                "linehead",
+                "auto-start-reverse",
+                options.style.markup_color(None),
+            ))
+            .append(options.arrowhead_style.marker(
+                "arrowhead",
+                "auto",
+                options.style.markup_color(None),
+            ))
+            .append_all(options.style.lit_defs(options.light_angle)?)
             .build()
     };
-    let diagram_width = f64::from(width) - 1.0 + 2.0 * BOARD_MARGIN + left_margin + right_margin;
+    let diagram_width =
+        f64::from(width) - 1.0 + 2.0 * options.board_margin + left_margin + right_margin;
+    let comment_list_width = if options.draw_comment_list && !goban.move_comments().is_empty() {
+        COMMENT_LIST_WIDTH
+    } else {
+        0.0
+    };
+    let snap_to_pixel_grid = options.snap_to_pixel_grid && options.viewbox_height.is_none();
+    let board_viewbox_width = match options.size {
+        // Make each board cell a whole number of pixels.
+        SizeSpec::TotalWidth(width) if snap_to_pixel_grid => {
+            (width / diagram_width).round().max(1.0) * diagram_width
+        }
+        SizeSpec::TotalWidth(width) => width,
+        SizeSpec::CellSize(cell_size) if snap_to_pixel_grid => {
+            cell_size.round().max(1.0) * diagram_width
+        }
+        SizeSpec::CellSize(cell_size) => cell_size * diagram_width,
+    };
+    // The comment list and padding widen the viewbox rather than shrinking the board.
+    let full_width = diagram_width + comment_list_width + 2.0 * options.padding;
+    let viewbox_width = board_viewbox_width
+        + board_viewbox_width * (comment_list_width + 2.0 * options.padding) / diagram_width;
+    // Round an offset in diagram units so it lands on a pixel boundary.
+    let snap = |offset: f64| {
+        if snap_to_pixel_grid {
+            let scale = board_viewbox_width / diagram_width;
+            (offset * scale).round() / scale
+        } else {
+            offset
+        }
+    };
+    // Tilting the board away from the viewer foreshortens it vertically.
+    let vertical_scale = options
+        .perspective_tilt
+        .map(|tilt| tilt.to_radians().cos())
+        .unwrap_or(1.0);
 
     let (diagram, diagram_height) = {
-        let board = build_board(&goban, options);
+        let board = build_board(goban, (&x_range, &y_range), options);
         let board_view = {
             let board_view_transform = format!(
                 "translate({}, {})",
-                format_float(BOARD_MARGIN + left_margin - f64::from(x_range.start)),
-                format_float(BOARD_MARGIN + top_margin - f64::from(y_range.start))
+                format_float(snap(options.board_margin + left_margin) - f64::from(x_range.start)),
+                format_float(snap(options.board_margin + top_margin) - f64::from(y_range.start))
             );
-            Element::builder("g", NAMESPACE)
+            let mut board_view_builder = Element::builder("g", NAMESPACE)
                 .attr("id", "board-view")
                 .attr("transform", board_view_transform)
-                .append(board)
-                .build()
+                .append(board);
+            if fade_crop_edges {
+                board_view_builder = board_view_builder.append(build_crop_fade_overlay(
+                    &x_range,
+                    &y_range,
+                    goban.size(),
+                    options,
+                ));
+            }
+            if options.draw_result_stamp && goban.is_last_node() {
+                if let Some(result) = goban.game_info().result.as_deref() {
+                    if !result.is_empty() {
+                        board_view_builder = board_view_builder.append(draw_result_stamp(
+                            &options.result_format.format(result),
+                            (&x_range, &y_range),
+                            options,
+                        ));
+                    }
+                }
+            }
+            if options.draw_info_box {
+                board_view_builder = board_view_builder.append(draw_info_box(
+                    goban.game_info(),
+                    (&x_range, &y_range),
+                    options,
+                ));
+            }
+            board_view_builder.build()
         };
 
-        let scale = format_float(options.viewbox_width / diagram_width);
-        let transform = format!("scale({}, {})", scale, scale);
-        let mut diagram_builder = Element::builder("g", NAMESPACE)
-            .attr("id", "diagram")
-            .attr("transform", transform)
-            .append(board_view);
+        let mut diagram_builder = Element::builder("g", NAMESPACE).append(board_view);
 
         if !options.label_sides.is_empty() {
-            let goban_size = goban.size();
-            diagram_builder = diagram_builder.append(draw_board_labels(
-                x_range,
-                goban_size.1 - height - y_range.start + 1..goban_size.1 - y_range.start + 1,
-                options,
-            ));
+            diagram_builder =
+                diagram_builder.append(draw_board_labels(x_range, y_range, goban.size(), options));
         }
 
         let mut diagram_height =
-            f64::from(height) - 1.0 + 2.0 * BOARD_MARGIN + top_margin + bottom_margin;
+            f64::from(height) - 1.0 + 2.0 * options.board_margin + top_margin + bottom_margin;
         if options.kifu_mode {
             if let Some((element, element_height)) = draw_repeated_stones(
-                &goban,
+                goban,
                 width,
                 diagram_height + REPEATED_MOVES_MARGIN,
                 options,
@@ -122,75 +1134,330 @@ pub fn make_svg(sgf: &str, options: &MakeSvgOptions) -> Result<Element, MakeSvgE
             }
         }
 
-        (diagram_builder.build(), diagram_height)
+        if options.draw_passes {
+            let passes: Vec<String> = goban
+                .passes()
+                .map(|(n, color)| {
+                    let color = match color {
+                        StoneColor::Black => "Black",
+                        StoneColor::White => "White",
+                    };
+                    format!("Move {}: {} pass", n, color)
+                })
+                .collect();
+            if !passes.is_empty() {
+                let (element, element_height) = draw_caption(
+                    &passes.join("\n"),
+                    "passes",
+                    diagram_width,
+                    diagram_height + CAPTION_MARGIN,
+                    options,
+                );
+                diagram_builder = diagram_builder.append(element);
+                diagram_height += element_height + CAPTION_MARGIN * 2.0;
+            }
+        }
+
+        if options.draw_comment {
+            if let Some(comment) = goban.comment() {
+                let (element, element_height) = draw_caption(
+                    comment,
+                    "caption",
+                    diagram_width,
+                    diagram_height + CAPTION_MARGIN,
+                    options,
+                );
+                diagram_builder = diagram_builder.append(element);
+                diagram_height += element_height + CAPTION_MARGIN * 2.0;
+            }
+        }
+
+        if options.draw_legend {
+            if let Some((element, element_height)) =
+                draw_legend(goban, diagram_height + CAPTION_MARGIN, options)
+            {
+                diagram_builder = diagram_builder.append(element);
+                diagram_height += element_height + CAPTION_MARGIN * 2.0;
+            }
+        }
+
+        if comment_list_width > 0.0 {
+            let (element, element_height) = draw_comment_list(goban, diagram_width, options);
+            diagram_builder = diagram_builder.append(element);
+            diagram_height = diagram_height.max(element_height + CAPTION_MARGIN);
+        }
+
+        let mut header_lines = vec![];
+        if options.draw_title {
+            if let Some(title) = goban.game_info().formatted_title(options.result_format) {
+                header_lines.push(("title", title));
+            }
+        }
+        if options.draw_captures {
+            let captures = format!(
+                "Black captures: {} / White captures: {}",
+                goban.captures_black(),
+                goban.captures_white()
+            );
+            header_lines.push(("captures", captures));
+        }
+        let diagram_builder = if header_lines.is_empty() {
+            diagram_builder
+        } else {
+            let mut header_builder = Element::builder("g", NAMESPACE);
+            let mut header_height = 0.0;
+            for (id, text) in &header_lines {
+                let (element, element_height) = draw_caption(
+                    text,
+                    id,
+                    diagram_width,
+                    header_height + CAPTION_MARGIN,
+                    options,
+                );
+                header_builder = header_builder.append(element);
+                header_height += element_height + CAPTION_MARGIN * 2.0;
+            }
+            let header_height = snap(header_height);
+            diagram_height += header_height;
+            header_builder.append(
+                diagram_builder
+                    .attr(
+                        "transform",
+                        format!("translate(0, {})", format_float(header_height)),
+                    )
+                    .build(),
+            )
+        };
+
+        (diagram_builder, diagram_height)
+    };
+
+    let natural_height = diagram_height * vertical_scale + 2.0 * options.padding;
+    let (viewbox_height, transform) = match options.viewbox_height {
+        Some(viewbox_height) => {
+            // Fit the diagram inside the viewbox and center it.
+            let scale = (viewbox_width / full_width).min(viewbox_height / natural_height);
+            let transform = format!(
+                "translate({}, {}) scale({}, {})",
+                format_float((viewbox_width - full_width * scale) / 2.0 + options.padding * scale),
+                format_float(
+                    (viewbox_height - natural_height * scale) / 2.0 + options.padding * scale
+                ),
+                format_float(scale),
+                format_float(scale * vertical_scale)
+            );
+            (viewbox_height, transform)
+        }
+        None => {
+            let scale = viewbox_width / full_width;
+            let offset = options.padding * scale;
+            let transform = if offset > 0.0 {
+                format!(
+                    "translate({}, {}) scale({}, {})",
+                    format_float(offset),
+                    format_float(offset),
+                    format_float(scale),
+                    format_float(scale * vertical_scale)
+                )
+            } else {
+                format!(
+                    "scale({}, {})",
+                    format_float(scale),
+                    format_float(scale * vertical_scale)
+                )
+            };
+            (natural_height * scale, transform)
+        }
     };
+    let diagram = diagram
+        .attr("id", "diagram")
+        .attr("transform", transform)
+        .build();
+    Ok(GobanLayout {
+        definitions,
+        diagram,
+        viewbox_width,
+        viewbox_height,
+    })
+}
 
+pub(crate) fn render_goban(
+    goban: &Goban,
+    options: &MakeSvgOptions,
+) -> Result<Element, MakeSvgError> {
+    let GobanLayout {
+        definitions,
+        diagram,
+        viewbox_width,
+        viewbox_height,
+    } = layout_goban(goban, options)?;
+    let background_fill = match options.board_texture {
+        Some(_) => "url(#board-texture)",
+        None => options.style.background_fill(),
+    };
     let background = Element::builder("rect", NAMESPACE)
-        .attr("fill", options.style.background_fill())
+        .attr("fill", background_fill)
         .attr("height", "100%")
         .attr("width", "100%")
         .attr("x", "0")
         .attr("y", "0")
         .build();
-
-    let viewbox_height = options.viewbox_width * diagram_height / diagram_width;
     let viewbox_attr = format!(
         "0 0 {} {}",
-        format_float(options.viewbox_width),
+        format_float(viewbox_width),
         format_float(viewbox_height)
     );
-    let svg = Element::builder("svg", NAMESPACE)
+    let mut svg_builder = Element::builder("svg", NAMESPACE)
         .attr("viewBox", viewbox_attr)
-        .attr("width", options.viewbox_width.to_string())
-        .attr("font-size", FONT_SIZE.to_string())
-        .attr("font-family", FONT_FAMILY)
-        .attr("font-weight", FONT_WEIGHT)
-        .append(definitions)
-        .append(background)
-        .append(diagram)
-        .build();
-    Ok(svg)
+        .attr("width", viewbox_width.to_string())
+        .attr("font-size", options.font_size.to_string())
+        .attr("font-family", options.font_family.as_str())
+        .attr("font-weight", options.font_weight);
+    if let Some(value) = options.shape_rendering.attr_value() {
+        svg_builder = svg_builder.attr("shape-rendering", value);
+    }
+    if options.generate_a11y || options.accessible_description.is_some() {
+        let title = goban
+            .game_info()
+            .formatted_title(options.result_format)
+            .unwrap_or_else(|| "Go board".to_string());
+        let description = match &options.accessible_description {
+            Some(description) => description.clone(),
+            None => {
+                let (width, height) = goban.size();
+                format!(
+                    "Go board position, move {}, {}x{}",
+                    goban.move_number(),
+                    width,
+                    height
+                )
+            }
+        };
+        svg_builder = svg_builder
+            .attr("role", "img")
+            .append(Element::builder("title", NAMESPACE).append(title))
+            .append(Element::builder("desc", NAMESPACE).append(description));
+    }
+    svg_builder = svg_builder.append(definitions);
+    if !options.transparent_background {
+        svg_builder = svg_builder.append(background);
+    }
+    Ok(finish_svg(svg_builder.append(diagram).build(), options))
+}
+
+/// Apply the options which rewrite the whole document: `inline_styles` and
+/// `coordinate_precision`.
+pub(crate) fn finish_svg(svg: Element, options: &MakeSvgOptions) -> Element {
+    let svg = match options.coordinate_precision {
+        Some(precision) => with_rounded_numbers(&svg, precision),
+        None => svg,
+    };
+    if options.inline_styles {
+        svg
+    } else {
+        extract_style_classes(&svg)
+    }
 }
 
 /// Draws a goban with squares of unit size.
-fn build_board(goban: &Goban, options: &MakeSvgOptions) -> Element {
+fn build_board(
+    goban: &Goban,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "goban")
-        .attr("clip-path", "url(#board-clip)")
-        .append(build_board_lines_group(goban, options))
-        .append(build_stones_group(goban, options));
-
+        .attr("clip-path", "url(#board-clip)");
+    if !options.influence.is_empty() {
+        group_builder = group_builder.append(build_influence_group(options));
+    }
+    if options.draw_grid_lines || options.draw_hoshi {
+        group_builder = group_builder.append(build_board_lines_group(goban, options));
+    }
+    if options.draw_all_points {
+        group_builder = group_builder.append(build_all_points_group(goban, ranges, options));
+    }
     let move_numbers = get_move_numbers(goban, options);
-    let no_markup_points: HashSet<(u8, u8)> = move_numbers
+    let mut no_markup_points: HashSet<(u8, u8)> = move_numbers
         .iter()
         .map(|(_, stone)| (stone.x, stone.y))
         .collect();
-    if let Some(move_number_options) = &options.move_number_options {
-        group_builder = group_builder.append(build_move_numbers_group(
-            goban,
-            options,
-            move_number_options,
-            &move_numbers,
-        ));
+    if options.numbered_stones_only {
+        no_markup_points.extend(goban.stones().map(|stone| (stone.x, stone.y)));
     }
-    if options.draw_marks {
-        group_builder = group_builder.append(build_marks_group(goban, options, &no_markup_points));
+    let markup_groups: [(MarkupKind, bool, MarkupGroupBuilder); 5] = [
+        (MarkupKind::Mark, options.draw_marks, build_marks_group),
+        (
+            MarkupKind::Triangle,
+            options.draw_triangles,
+            build_triangles_group,
+        ),
+        (
+            MarkupKind::Circle,
+            options.draw_circles,
+            build_circles_group,
+        ),
+        (
+            MarkupKind::Square,
+            options.draw_squares,
+            build_squares_group,
+        ),
+        (
+            MarkupKind::Selected,
+            options.draw_selected,
+            build_selected_group,
+        ),
+    ];
+    for &(kind, drawn, build) in &markup_groups {
+        if drawn && options.background_markup.contains(&kind) {
+            group_builder = group_builder.append(build(goban, options, &no_markup_points));
+        }
     }
-    if options.draw_triangles {
-        group_builder =
-            group_builder.append(build_triangles_group(goban, options, &no_markup_points));
+    group_builder = group_builder.append(build_stones_group(goban, options));
+    if options.draw_territory {
+        group_builder = group_builder.append(build_territory_group(goban, options));
     }
-    if options.draw_circles {
-        group_builder =
-            group_builder.append(build_circles_group(goban, options, &no_markup_points));
+
+    if options.draw_move_trail {
+        group_builder = group_builder.append(build_move_trail_group(goban, options, &move_numbers));
     }
-    if options.draw_squares {
-        group_builder =
-            group_builder.append(build_squares_group(goban, options, &no_markup_points));
+    let mut move_numbers_group = options
+        .move_number_options
+        .as_ref()
+        .map(|move_number_options| {
+            build_move_numbers_group(goban, options, move_number_options, &move_numbers)
+        });
+    if !options.move_numbers_on_top {
+        if let Some(group) = move_numbers_group.take() {
+            group_builder = group_builder.append(group);
+        }
     }
-    if options.draw_selected {
+    if options.draw_last_move {
         group_builder =
-            group_builder.append(build_selected_group(goban, options, &no_markup_points));
+            group_builder.append(build_last_move_group(goban, options, &no_markup_points));
+    }
+    if options.draw_ko {
+        group_builder = group_builder.append(build_ko_group(goban, options));
+    }
+    if options.draw_capture_markers {
+        group_builder = group_builder.append(build_capture_markers_group(goban, options));
+    }
+    if !options.highlight_moves.is_empty() {
+        group_builder = group_builder.append(build_highlight_group(goban, options));
+    }
+    if !options.outline_groups.is_empty() {
+        group_builder = group_builder.append(build_group_outline_group(goban, options));
+    }
+    if !options.annotate_liberties.is_empty() {
+        group_builder = group_builder.append(build_liberties_group(goban, options));
+    }
+    if !options.solution_marks.is_empty() {
+        group_builder = group_builder.append(build_solution_group(options));
+    }
+    for &(kind, drawn, build) in &markup_groups {
+        if drawn && !options.background_markup.contains(&kind) {
+            group_builder = group_builder.append(build(goban, options, &no_markup_points));
+        }
     }
     if options.draw_dimmed {
         group_builder = group_builder.append(build_dimmed_group(goban, options));
@@ -199,70 +1466,203 @@ fn build_board(goban: &Goban, options: &MakeSvgOptions) -> Element {
         group_builder = group_builder.append(build_label_group(goban, options, &no_markup_points));
     }
     if options.draw_lines {
-        group_builder = group_builder.append(build_line_group(goban, options));
+        group_builder = group_builder.append(build_line_group(goban, ranges, options));
     }
     if options.draw_arrows {
-        group_builder = group_builder.append(build_arrow_group(goban, options));
+        group_builder = group_builder.append(build_arrow_group(goban, ranges, options));
+    }
+    if let Some(group) = move_numbers_group {
+        group_builder = group_builder.append(group);
+    }
+    if options.embed_point_titles {
+        group_builder = group_builder.append(build_point_titles_group(goban, options));
     }
 
     group_builder.build()
 }
 
 fn build_board_lines_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let goban_size = goban.size();
+    let mut line_width = options.style.line_width();
+    if let Some(threshold) = options.line_thinning_above {
+        let lines = goban_size.0.max(goban_size.1);
+        if lines > threshold {
+            line_width *= f64::from(threshold) / f64::from(lines);
+        }
+    }
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "lines")
-        .attr("stroke", options.style.line_color())
-        .attr("stroke-width", format_float(options.style.line_width()))
+        .attr(
+            "stroke",
+            (!options.emit_css_classes).then(|| options.style.line_color()),
+        )
+        .attr("stroke-width", format_float(line_width))
         .attr("stroke-linecap", "square");
+    if options.clip_lines_under_stones {
+        group_builder = group_builder.attr("mask", "url(#stones-mask)");
+    }
 
     // Draw lines
-    let goban_size = goban.size();
-    for x in 0..goban_size.0 as usize {
-        group_builder = group_builder.append(
-            Element::builder("line", NAMESPACE)
-                .attr("x1", x.to_string())
-                .attr("y1", "0")
-                .attr("x2", x.to_string())
-                .attr("y2", (goban_size.1 - 1).to_string()),
-        );
-    }
-    for y in 0..goban_size.1 as usize {
-        group_builder = group_builder.append(
-            Element::builder("line", NAMESPACE)
-                .attr("x1", 0.to_string())
-                .attr("y1", y.to_string())
-                .attr("x2", (goban_size.0 - 1).to_string())
-                .attr("y2", y.to_string()),
-        );
+    if options.draw_grid_lines {
+        for x in 0..goban_size.0 as usize {
+            group_builder = group_builder.append(
+                Element::builder("line", NAMESPACE)
+                    .attr("x1", x.to_string())
+                    .attr("y1", "0")
+                    .attr("x2", x.to_string())
+                    .attr("y2", (goban_size.1 - 1).to_string())
+                    .attr("class", css_class(options, "board-line")),
+            );
+        }
+        for y in 0..goban_size.1 as usize {
+            group_builder = group_builder.append(
+                Element::builder("line", NAMESPACE)
+                    .attr("x1", 0.to_string())
+                    .attr("y1", y.to_string())
+                    .attr("x2", (goban_size.0 - 1).to_string())
+                    .attr("y2", y.to_string())
+                    .attr("class", css_class(options, "board-line")),
+            );
+        }
     }
+    let hoshi_radius = match options.style.hoshi_radius() {
+        Some(radius) if options.draw_hoshi => radius,
+        _ => return group_builder.build(),
+    };
 
     // Draw hoshi
-    let hoshi_radius = options.style.hoshi_radius();
     let mut hoshi = Element::builder("g", NAMESPACE)
         .attr("id", "hoshi")
         .attr("stroke", "none")
-        .attr("fill", options.style.line_color());
-    for (x, y) in goban.hoshi_points() {
+        .attr(
+            "fill",
+            (!options.emit_css_classes).then(|| options.style.hoshi_fill()),
+        );
+    for (x, y) in hoshi_points(goban, options) {
         hoshi = hoshi.append(
             Element::builder("circle", NAMESPACE)
                 .attr("cx", x.to_string())
                 .attr("cy", y.to_string())
-                .attr("r", format_float(hoshi_radius)),
+                .attr("r", format_float(hoshi_radius))
+                .attr("class", css_class(options, "hoshi")),
         );
     }
     group_builder.append(hoshi).build()
 }
 
+/// Draw a faint dot on each empty point in the drawn range, the size of a hoshi.
+fn build_all_points_group(
+    goban: &Goban,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
+    let (x_range, y_range) = ranges;
+    let point_radius = options
+        .style
+        .hoshi_radius()
+        .unwrap_or_else(default_hoshi_radius);
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "all-points")
+        .attr("fill", options.style.line_color())
+        .attr("fill-opacity", format_float(POINT_MARKER_OPACITY));
+    for y in y_range.clone() {
+        for x in x_range.clone() {
+            if goban.stone_at((x, y)).is_none() {
+                group_builder = group_builder.append(
+                    Element::builder("circle", NAMESPACE)
+                        .attr("cx", x.to_string())
+                        .attr("cy", y.to_string())
+                        .attr("r", format_float(point_radius)),
+                );
+            }
+        }
+    }
+    group_builder.build()
+}
+
+/// Hoshi to draw, using `options.hoshi_points` if set. Points off the board are skipped.
+pub(crate) fn hoshi_points(goban: &Goban, options: &MakeSvgOptions) -> Vec<(u8, u8)> {
+    if options.hoshi_points.is_empty() {
+        return goban.hoshi_points().collect();
+    }
+    let (width, height) = goban.size();
+    options
+        .hoshi_points
+        .iter()
+        .copied()
+        .filter(|&(x, y)| x < width && y < height)
+        .collect()
+}
+
 fn build_stones_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "stones")
         .attr("stroke", "none");
+    if options.stone_opacity < 1.0 {
+        group_builder = group_builder.attr("fill-opacity", format_float(options.stone_opacity));
+    }
+    let stone_moves = if options.layer_moves {
+        stone_moves(goban, options)
+    } else {
+        HashMap::new()
+    };
+    for stone in displayed_stones(goban, options) {
+        let class = match stone.color {
+            StoneColor::Black => "stone black",
+            StoneColor::White => "stone white",
+        };
+        let mut element = with_css_class(draw_stone(stone, options), class, options);
+        if let Some(fill) = options.stone_overrides.get(&(stone.x, stone.y)) {
+            element.set_attr("fill", fill.as_str());
+        }
+        if options.dead_stones.contains(&(stone.x, stone.y)) {
+            element.set_attr("opacity", format_float(DEAD_STONE_OPACITY));
+        } else if options.dim_setup_stones
+            && goban.is_setup_stone(stone.x, stone.y)
+            && !is_ghosted(stone.color, options)
+        {
+            element.set_attr("opacity", "0.5");
+        }
+        if let Some(n) = stone_moves.get(&(stone.x, stone.y)) {
+            element.set_attr("id", format!("move-{}-stone", n));
+            element.set_attr("data-move", n.to_string());
+        }
+        group_builder = group_builder.append(element);
+    }
+    group_builder.build()
+}
+
+/// The move which placed each stone drawn by `displayed_stones`, leaving out setup stones.
+fn stone_moves(goban: &Goban, options: &MakeSvgOptions) -> HashMap<(u8, u8), u64> {
+    let mut stone_moves = HashMap::new();
+    let mut numbered_moves = HashMap::new();
+    for (n, stone) in goban.moves() {
+        let point = (stone.x, stone.y);
+        if options.kifu_mode
+            && options
+                .move_number_options
+                .is_some_and(|move_number_options| n >= move_number_options.start)
+        {
+            numbered_moves.entry(point).or_insert(n);
+        }
+        stone_moves.insert(point, n);
+    }
+    stone_moves.extend(numbered_moves);
+    stone_moves.retain(|&(x, y), _| !goban.is_setup_stone(x, y));
+    stone_moves
+}
+
+/// The stones to draw, sorted by position.
+fn displayed_stones(goban: &Goban, options: &MakeSvgOptions) -> Vec<Stone> {
     let mut stones: Vec<Stone> = if options.kifu_mode {
         // For each intersection draw the first numbered stone, or the last non-numbered stone.
         let mut stones: HashMap<(u8, u8), Stone> = HashMap::new();
         let mut numbered_stones: HashMap<(u8, u8), Stone> = HashMap::new();
         for (n, stone) in goban.moves() {
-            if n >= options.move_number_options.unwrap().start {
+            if options
+                .move_number_options
+                .is_some_and(|move_number_options| n >= move_number_options.start)
+            {
                 numbered_stones.entry((stone.x, stone.y)).or_insert(stone);
             }
             stones.insert((stone.x, stone.y), stone);
@@ -275,8 +1675,159 @@ fn build_stones_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
         goban.stones().collect()
     };
     stones.sort_by_key(|stone| (stone.y, stone.x));
-    for stone in stones {
-        group_builder = group_builder.append(draw_stone(stone, &options.style));
+    stones
+}
+
+/// A mask hiding everything under the stones, for use on the board lines.
+fn build_stones_mask(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let (width, height) = goban.size();
+    let mut mask_builder = Element::builder("mask", NAMESPACE)
+        .attr("id", "stones-mask")
+        .attr("maskUnits", "userSpaceOnUse")
+        .attr("x", "-1")
+        .attr("y", "-1")
+        .attr("width", (width + 1).to_string())
+        .attr("height", (height + 1).to_string())
+        .append(
+            Element::builder("rect", NAMESPACE)
+                .attr("x", "-1")
+                .attr("y", "-1")
+                .attr("width", (width + 1).to_string())
+                .attr("height", (height + 1).to_string())
+                .attr("fill", "white"),
+        );
+    for stone in displayed_stones(goban, options) {
+        mask_builder = mask_builder.append(
+            Element::builder("circle", NAMESPACE)
+                .attr("cx", stone.x)
+                .attr("cy", stone.y)
+                .attr("r", format_float(options.stone_radius))
+                .attr("fill", "black"),
+        );
+    }
+    mask_builder.build()
+}
+
+/// Gradients from transparent to the background color, pointing out from the board on each
+/// side, for `build_crop_fade_overlay`.
+fn build_crop_fade_gradients(options: &MakeSvgOptions) -> Vec<Element> {
+    let sides = [
+        ("left", (1, 0, 0, 0)),
+        ("right", (0, 0, 1, 0)),
+        ("top", (0, 1, 0, 0)),
+        ("bottom", (0, 0, 0, 1)),
+    ];
+    sides
+        .iter()
+        .map(|(side, (x1, y1, x2, y2))| {
+            Element::builder("linearGradient", NAMESPACE)
+                .attr("id", format!("crop-fade-{}", side))
+                .attr("x1", x1.to_string())
+                .attr("y1", y1.to_string())
+                .attr("x2", x2.to_string())
+                .attr("y2", y2.to_string())
+                .append(
+                    Element::builder("stop", NAMESPACE)
+                        .attr("offset", "0")
+                        .attr("stop-color", options.style.background_fill())
+                        .attr("stop-opacity", "0"),
+                )
+                .append(
+                    Element::builder("stop", NAMESPACE)
+                        .attr("offset", "1")
+                        .attr("stop-color", options.style.background_fill()),
+                )
+                .build()
+        })
+        .collect()
+}
+
+/// Build strips which fade the board into the background past the last drawn line on each
+/// side where the range is cropped, so it's clear the board continues.
+fn build_crop_fade_overlay(
+    x_range: &Range<u8>,
+    y_range: &Range<u8>,
+    goban_size: (u8, u8),
+    options: &MakeSvgOptions,
+) -> Element {
+    // The edges of the clipped region.
+    let (left, top, right, bottom) = (
+        f64::from(x_range.start) - 0.5 - options.clip_margin,
+        f64::from(y_range.start) - 0.5 - options.clip_margin,
+        f64::from(x_range.end) - 0.5 + options.clip_margin,
+        f64::from(y_range.end) - 0.5 + options.clip_margin,
+    );
+    // Each strip runs from the last drawn line to the clip edge.
+    let mut strips = vec![];
+    if x_range.start > 0 {
+        strips.push(("left", (left, top, f64::from(x_range.start), bottom)));
+    }
+    if x_range.end < goban_size.0 {
+        strips.push(("right", (f64::from(x_range.end - 1), top, right, bottom)));
+    }
+    if y_range.start > 0 {
+        strips.push(("top", (left, top, right, f64::from(y_range.start))));
+    }
+    if y_range.end < goban_size.1 {
+        strips.push(("bottom", (left, f64::from(y_range.end - 1), right, bottom)));
+    }
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "crop-fade");
+    for (side, (x1, y1, x2, y2)) in strips {
+        group_builder = group_builder.append(
+            Element::builder("rect", NAMESPACE)
+                .attr("x", format_float(x1))
+                .attr("y", format_float(y1))
+                .attr("width", format_float(x2 - x1))
+                .attr("height", format_float(y2 - y1))
+                .attr("fill", format!("url(#crop-fade-{})", side)),
+        );
+    }
+    group_builder.build()
+}
+
+fn build_territory_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "territory");
+    for (color, points) in [
+        (StoneColor::Black, goban.black_territory().collect()),
+        (StoneColor::White, goban.white_territory().collect()),
+    ] {
+        let mut points: Vec<(u8, u8)> = points;
+        points.sort_unstable();
+        for (x, y) in points {
+            group_builder = group_builder.append(draw_territory(x, y, color, &options.style));
+        }
+    }
+    group_builder.build()
+}
+
+/// Fill the cell around each point with influence, colored by its value.
+fn build_influence_group(options: &MakeSvgOptions) -> Element {
+    // Translucent so the board shows through, and stones stand out against either end.
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "influence")
+        .attr("fill-opacity", "0.5");
+    let black = parse_rgb(&options.influence_colors.0).unwrap_or((0, 0, 0));
+    let white = parse_rgb(&options.influence_colors.1).unwrap_or((255, 255, 255));
+    let mut points: Vec<_> = options.influence.iter().collect();
+    points.sort_unstable_by_key(|(point, _)| **point);
+    for (&(x, y), &value) in points {
+        // 0 at the black end of the scale, 1 at the white end.
+        let t = (value.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        let channel = |b: u8, w: u8| (f64::from(b) + (f64::from(w) - f64::from(b)) * t).round();
+        let fill = format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(black.0, white.0) as u8,
+            channel(black.1, white.1) as u8,
+            channel(black.2, white.2) as u8
+        );
+        group_builder = group_builder.append(
+            Element::builder("rect", NAMESPACE)
+                .attr("x", format_float(f64::from(x) - 0.5))
+                .attr("y", format_float(f64::from(y) - 0.5))
+                .attr("width", "1")
+                .attr("height", "1")
+                .attr("fill", fill),
+        );
     }
     group_builder.build()
 }
@@ -290,6 +1841,24 @@ fn build_move_numbers_group(
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "move-numbers")
         .attr("text-anchor", "middle");
+    // Every numbered move at each point, for points played more than once.
+    let mut numbers_at_point: HashMap<(u8, u8), Vec<u64>> = HashMap::new();
+    if options.show_all_numbers_at_point && !options.kifu_mode {
+        for (n, stone) in goban.moves() {
+            if n >= move_number_options.start
+                && move_number_options.end.is_none_or(|end| n <= end)
+                && options
+                    .number_only_moves
+                    .as_ref()
+                    .is_none_or(|moves| moves.contains(&n))
+            {
+                numbers_at_point
+                    .entry((stone.x, stone.y))
+                    .or_default()
+                    .push(n);
+            }
+        }
+    }
     for (n, stone) in move_numbers {
         let stone_color = if options.kifu_mode {
             // In kifu mode, the first numbered stone played will be shown.
@@ -298,19 +1867,49 @@ fn build_move_numbers_group(
             // Otherwise, we can look at the board.
             goban.stone_color(stone.x, stone.y)
         };
-        let move_number = n + move_number_options.count_from - move_number_options.start;
-        group_builder = group_builder.append(draw_move_number(
-            stone.x,
-            stone.y,
-            move_number,
-            stone_color,
-            &options.style,
-        ));
+        let stacked_numbers = numbers_at_point
+            .get(&(stone.x, stone.y))
+            .filter(|numbers| numbers.len() > 1);
+        let text = match stacked_numbers {
+            Some(numbers) => numbers
+                .iter()
+                .map(|&n| move_number_options.display_number(n).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            None => move_number_options.display_number(*n).to_string(),
+        };
+        let fill = options
+            .move_number_color_ranges
+            .iter()
+            .find(|(range, _)| range.contains(n))
+            .map(|(_, color)| color.to_string())
+            .unwrap_or_else(|| {
+                if options.parity_tint {
+                    options.style.parity_move_number_color(stone_color, *n)
+                } else {
+                    options.style.move_number_color(stone_color).to_string()
+                }
+            });
+        let mut element = with_css_class(
+            draw_move_number(stone.x, stone.y, &text, stone_color, &fill, options),
+            "move-number",
+            options,
+        );
+        if stacked_numbers.is_some() && text.len() > 2 {
+            // Shrink stacks of numbers to fit on the stone.
+            let font_size = options.font_size * 2.0 / text.len() as f64;
+            element.set_attr("font-size", format_float(font_size));
+        }
+        if options.layer_moves {
+            element.set_attr("id", format!("move-{}-number", n));
+            element.set_attr("data-move", n.to_string());
+        }
+        group_builder = group_builder.append(element);
     }
     group_builder.build()
 }
 
-fn get_move_numbers(goban: &Goban, options: &MakeSvgOptions) -> Vec<(u64, Stone)> {
+pub(crate) fn get_move_numbers(goban: &Goban, options: &MakeSvgOptions) -> Vec<(u64, Stone)> {
     let move_number_options = match options.move_number_options {
         Some(move_number_options) => move_number_options,
         None => return Vec::new(),
@@ -329,9 +1928,313 @@ fn get_move_numbers(goban: &Goban, options: &MakeSvgOptions) -> Vec<(u64, Stone)
             move_numbers.insert((stone.x, stone.y), (n, stone));
         }
     }
-    let mut move_numbers: Vec<(u64, Stone)> = move_numbers.values().copied().collect();
-    move_numbers.sort_unstable_by_key(|(n, _)| *n);
-    move_numbers
+    let mut move_numbers: Vec<(u64, Stone)> = move_numbers
+        .values()
+        .copied()
+        .filter(|(n, _)| {
+            options
+                .number_only_moves
+                .as_ref()
+                .is_none_or(|moves| moves.contains(n))
+        })
+        .collect();
+    move_numbers.sort_unstable_by_key(|(n, _)| *n);
+    move_numbers
+}
+
+fn build_move_trail_group(
+    goban: &Goban,
+    options: &MakeSvgOptions,
+    move_numbers: &[(u64, Stone)],
+) -> Element {
+    let points: Vec<String> = move_numbers
+        .iter()
+        .filter(|(_, stone)| goban.stone_color(stone.x, stone.y) == Some(stone.color))
+        .map(|(_, stone)| format!("{},{}", stone.x, stone.y))
+        .collect();
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "move-trail")
+        .attr("fill", "none")
+        .attr("stroke", options.style.markup_color(None))
+        .attr("stroke-width", format_float(options.style.line_width()))
+        .attr("stroke-opacity", format_float(MOVE_TRAIL_OPACITY));
+    if points.len() > 1 {
+        group_builder = group_builder
+            .append(Element::builder("polyline", NAMESPACE).attr("points", points.join(" ")));
+    }
+    group_builder.build()
+}
+
+fn build_last_move_group(
+    goban: &Goban,
+    options: &MakeSvgOptions,
+    no_markup_points: &HashSet<(u8, u8)>,
+) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "last-move");
+    if let Some((x, y)) = goban.last_move() {
+        let stone_color = goban.stone_color(x, y);
+        if stone_color.is_some() && !no_markup_points.contains(&(x, y)) {
+            group_builder = group_builder.append(
+                Element::builder("circle", NAMESPACE)
+                    .attr("cx", x)
+                    .attr("cy", y)
+                    .attr("r", "0.12")
+                    .attr("stroke", "none")
+                    .attr("fill", options.style.markup_color(stone_color)),
+            );
+        }
+    }
+    group_builder.build()
+}
+
+fn build_ko_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "ko");
+    if let Some((x, y)) = goban.ko() {
+        group_builder = group_builder.append(draw_square(x, y, None, &options.style));
+    }
+    group_builder.build()
+}
+
+fn build_capture_markers_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "capture-markers");
+    for &(x, y) in goban.last_captures() {
+        group_builder = group_builder.append(draw_mark(x, y, None, &options.style));
+    }
+    group_builder.build()
+}
+
+fn build_highlight_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "highlight")
+        .attr("fill", "none")
+        .attr(
+            "stroke-width",
+            format_float(options.style.markup_stroke_width()),
+        );
+    let mut points = options.highlight_moves.clone();
+    points.sort_unstable();
+    points.dedup();
+    for (x, y) in points {
+        let stone_color = goban.stone_color(x, y);
+        group_builder = group_builder.append(
+            Element::builder("circle", NAMESPACE)
+                .attr("cx", x)
+                .attr("cy", y)
+                .attr("r", "0.5")
+                .attr("stroke", options.style.selected_color(stone_color)),
+        );
+    }
+    group_builder.build()
+}
+
+/// Draw a green check or red cross at each solution mark, in the same colors in every style.
+fn build_solution_group(options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "solutions")
+        .attr("fill", "none")
+        .attr("stroke-width", format_float(SOLUTION_STROKE_WIDTH))
+        .attr("stroke-linecap", "round")
+        .attr("stroke-linejoin", "round");
+    for &((x, y), kind) in &options.solution_marks {
+        let (x, y) = (f64::from(x), f64::from(y));
+        let (color, path) = match kind {
+            SolutionKind::Correct => (
+                CORRECT_COLOR,
+                format!(
+                    "M{},{} L{},{} L{},{}",
+                    format_float(x - 0.25),
+                    format_float(y + 0.02),
+                    format_float(x - 0.07),
+                    format_float(y + 0.2),
+                    format_float(x + 0.27),
+                    format_float(y - 0.22),
+                ),
+            ),
+            SolutionKind::Wrong => (
+                WRONG_COLOR,
+                format!(
+                    "M{},{} L{},{} M{},{} L{},{}",
+                    format_float(x - 0.22),
+                    format_float(y - 0.22),
+                    format_float(x + 0.22),
+                    format_float(y + 0.22),
+                    format_float(x - 0.22),
+                    format_float(y + 0.22),
+                    format_float(x + 0.22),
+                    format_float(y - 0.22),
+                ),
+            ),
+        };
+        group_builder = group_builder.append(
+            Element::builder("path", NAMESPACE)
+                .attr("d", path)
+                .attr("stroke", color),
+        );
+    }
+    group_builder.build()
+}
+
+/// Outline each group containing one of `options.outline_groups`, in the selected color for
+/// its stones.
+fn build_group_outline_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "group-outlines")
+        .attr("fill", "none")
+        .attr(
+            "stroke-width",
+            format_float(options.style.markup_stroke_width()),
+        );
+    let mut seeds = options.outline_groups.clone();
+    seeds.sort_unstable();
+    let mut outlined = HashSet::new();
+    for point in seeds {
+        if outlined.contains(&point) {
+            continue;
+        }
+        let group = match goban.group(point) {
+            Some(group) => group,
+            None => continue,
+        };
+        let stone_color = goban.stone_color(point.0, point.1);
+        group_builder = group_builder.append(
+            Element::builder("path", NAMESPACE)
+                .attr("d", group_outline_path(&group))
+                .attr("stroke", options.style.selected_color(stone_color)),
+        );
+        outlined.extend(group);
+    }
+    group_builder.build()
+}
+
+/// An SVG path around the edges of the cells of `group`, with rounded corners.
+///
+/// Cell edges not shared with another cell of the group are joined into loops, one around the
+/// outside and one around each hole. Corners are indexed so corner `(x, y)` is at the top left
+/// of the cell of point `(x, y)`, and each edge runs clockwise around the group.
+fn group_outline_path(group: &HashSet<(u8, u8)>) -> String {
+    let corner_radius = 0.25;
+    let contains = |x: i32, y: i32| {
+        u8::try_from(x)
+            .and_then(|x| Ok((x, u8::try_from(y)?)))
+            .is_ok_and(|point| group.contains(&point))
+    };
+    let mut edges: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
+    for &(x, y) in group {
+        let (x, y) = (i32::from(x), i32::from(y));
+        let sides = [
+            ((x, y - 1), (x, y), (x + 1, y)),
+            ((x + 1, y), (x + 1, y), (x + 1, y + 1)),
+            ((x, y + 1), (x + 1, y + 1), (x, y + 1)),
+            ((x - 1, y), (x, y + 1), (x, y)),
+        ];
+        for &((nx, ny), start, end) in &sides {
+            if !contains(nx, ny) {
+                edges.entry(start).or_default().push(end);
+            }
+        }
+    }
+
+    let mut starts: Vec<(i32, i32)> = edges.keys().copied().collect();
+    starts.sort_unstable();
+    let mut path = vec![];
+    for start in starts {
+        let mut corners = vec![];
+        let mut corner = start;
+        let mut direction = (0, 0);
+        let mut first_direction = None;
+        loop {
+            let ends = match edges.get_mut(&corner) {
+                Some(ends) if !ends.is_empty() => ends,
+                _ => break,
+            };
+            // Where loops touch at a corner, keep to the tighter turn so they stay apart.
+            let turn = |end: &(i32, i32)| {
+                let next = (end.0 - corner.0, end.1 - corner.1);
+                direction.0 * next.1 - direction.1 * next.0
+            };
+            let index = (0..ends.len()).max_by_key(|&i| turn(&ends[i])).unwrap();
+            let end = ends.swap_remove(index);
+            let next = (end.0 - corner.0, end.1 - corner.1);
+            if next != direction {
+                corners.push(corner);
+            }
+            direction = next;
+            first_direction.get_or_insert(next);
+            corner = end;
+        }
+        if corners.is_empty() {
+            continue;
+        }
+        // The start was taken as a corner before the direction it's entered from was known.
+        if first_direction == Some(direction) {
+            corners.remove(0);
+        }
+        let count = corners.len();
+        let position = |(x, y): (i32, i32)| (f64::from(x) - 0.5, f64::from(y) - 0.5);
+        let toward = |from: (f64, f64), to: (f64, f64)| {
+            let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+            (
+                from.0 + (to.0 - from.0) * corner_radius / length,
+                from.1 + (to.1 - from.1) * corner_radius / length,
+            )
+        };
+        for i in 0..count {
+            let previous = position(corners[(i + count - 1) % count]);
+            let current = position(corners[i]);
+            let next = position(corners[(i + 1) % count]);
+            let before = toward(current, previous);
+            let after = toward(current, next);
+            path.push(format!(
+                "{}{},{} Q{},{} {},{}",
+                if i == 0 { "M" } else { "L" },
+                format_float(before.0),
+                format_float(before.1),
+                format_float(current.0),
+                format_float(current.1),
+                format_float(after.0),
+                format_float(after.1),
+            ));
+        }
+        path.push("Z".to_string());
+    }
+    path.join(" ")
+}
+
+/// Draw a small badge with the liberty count at the upper right of each requested stone.
+fn build_liberties_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "liberties")
+        .attr("text-anchor", "middle")
+        .attr("font-size", format_float(0.6 * options.font_size));
+    let mut points = options.annotate_liberties.clone();
+    points.sort_unstable();
+    points.dedup();
+    for (x, y) in points {
+        if let Some(liberties) = goban.liberties((x, y)) {
+            let stone_color = goban.stone_color(x, y);
+            let cx = format_float(f64::from(x) + 0.32);
+            let cy = format_float(f64::from(y) - 0.32);
+            group_builder = group_builder.append(
+                Element::builder("g", NAMESPACE)
+                    .append(
+                        Element::builder("circle", NAMESPACE)
+                            .attr("cx", cx.as_str())
+                            .attr("cy", cy.as_str())
+                            .attr("r", "0.2")
+                            .attr("fill", options.style.selected_color(stone_color)),
+                    )
+                    .append(
+                        Element::builder("text", NAMESPACE)
+                            .attr("x", cx.as_str())
+                            .attr("y", cy.as_str())
+                            .attr("dy", "0.35em")
+                            .attr("fill", options.style.background_fill())
+                            .append(liberties.to_string()),
+                    ),
+            );
+        }
+    }
+    group_builder.build()
 }
 
 fn build_marks_group(
@@ -344,8 +2247,11 @@ fn build_marks_group(
     marks.sort_unstable();
     for point in marks.iter().filter(|p| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder =
-            group_builder.append(draw_mark(point.0, point.1, stone_color, &options.style));
+        group_builder = group_builder.append(with_css_class(
+            draw_mark(point.0, point.1, stone_color, &options.style),
+            "markup-mark",
+            options,
+        ));
     }
     group_builder.build()
 }
@@ -360,8 +2266,11 @@ fn build_triangles_group(
     triangles.sort_unstable();
     for point in triangles.iter().filter(|p| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder =
-            group_builder.append(draw_triangle(point.0, point.1, stone_color, &options.style));
+        group_builder = group_builder.append(with_css_class(
+            draw_triangle(point.0, point.1, stone_color, &options.style),
+            "markup-triangle",
+            options,
+        ));
     }
     group_builder.build()
 }
@@ -376,8 +2285,11 @@ fn build_circles_group(
     circles.sort_unstable();
     for point in circles.iter().filter(|p| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder =
-            group_builder.append(draw_circle(point.0, point.1, stone_color, &options.style));
+        group_builder = group_builder.append(with_css_class(
+            draw_circle(point.0, point.1, stone_color, &options.style),
+            "markup-circle",
+            options,
+        ));
     }
     group_builder.build()
 }
@@ -392,8 +2304,11 @@ fn build_squares_group(
     squares.sort_unstable();
     for point in squares.iter().filter(|p| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder =
-            group_builder.append(draw_square(point.0, point.1, stone_color, &options.style));
+        group_builder = group_builder.append(with_css_class(
+            draw_square(point.0, point.1, stone_color, &options.style),
+            "markup-square",
+            options,
+        ));
     }
     group_builder.build()
 }
@@ -408,8 +2323,11 @@ fn build_selected_group(
     selected.sort_unstable();
     for point in selected.iter().filter(|p| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder =
-            group_builder.append(draw_selected(point.0, point.1, stone_color, &options.style));
+        group_builder = group_builder.append(with_css_class(
+            draw_selected(point.0, point.1, stone_color, &options.style),
+            "markup-selected",
+            options,
+        ));
     }
     group_builder.build()
 }
@@ -434,25 +2352,34 @@ fn build_label_group(
     labels.sort_unstable();
     for (point, text) in labels.iter().filter(|(p, _)| !no_markup_points.contains(p)) {
         let stone_color = goban.stone_color(point.0, point.1);
-        group_builder = group_builder.append(draw_label(
-            point.0,
-            point.1,
-            text,
-            stone_color,
-            &options.style,
+        group_builder = group_builder.append(with_css_class(
+            draw_label(point.0, point.1, text, stone_color, options),
+            "markup-label",
+            options,
         ));
     }
     group_builder.build()
 }
 
-fn build_line_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+fn build_line_group(
+    goban: &Goban,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "markup-lines")
-        .attr("stroke", "black")
+        .attr(
+            "stroke",
+            (!options.emit_css_classes).then(|| options.style.markup_color(None)),
+        )
         .attr("stroke-width", format_float(options.style.line_width()))
         .attr("marker-start", "url(#linehead)")
         .attr("marker-end", "url(#linehead)");
-    let mut lines: Vec<_> = goban.lines().collect();
+    group_builder = with_line_dash(group_builder, options);
+    let mut lines: Vec<_> = goban
+        .lines()
+        .filter(|&ends| is_annotation_drawn(ends, ranges, options))
+        .collect();
     lines.sort_unstable();
     for (p1, p2) in lines {
         group_builder = group_builder.append(
@@ -460,19 +2387,31 @@ fn build_line_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
                 .attr("x1", p1.0)
                 .attr("x2", p2.0)
                 .attr("y1", p1.1)
-                .attr("y2", p2.1),
+                .attr("y2", p2.1)
+                .attr("class", css_class(options, "markup-line")),
         );
     }
     group_builder.build()
 }
 
-fn build_arrow_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+fn build_arrow_group(
+    goban: &Goban,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
     let mut group_builder = Element::builder("g", NAMESPACE)
         .attr("id", "markup-arrows")
-        .attr("stroke", "black")
+        .attr(
+            "stroke",
+            (!options.emit_css_classes).then(|| options.style.markup_color(None)),
+        )
         .attr("stroke-width", format_float(options.style.line_width()))
         .attr("marker-end", "url(#arrowhead)");
-    let mut arrows: Vec<_> = goban.arrows().collect();
+    group_builder = with_line_dash(group_builder, options);
+    let mut arrows: Vec<_> = goban
+        .arrows()
+        .filter(|&ends| is_annotation_drawn(ends, ranges, options))
+        .collect();
     arrows.sort_unstable();
     for (p1, p2) in arrows {
         group_builder = group_builder.append(
@@ -480,18 +2419,221 @@ fn build_arrow_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
                 .attr("x1", p1.0)
                 .attr("x2", p2.0)
                 .attr("y1", p1.1)
-                .attr("y2", p2.1),
+                .attr("y2", p2.1)
+                .attr("class", css_class(options, "markup-arrow")),
         );
     }
 
     group_builder.build()
 }
 
+/// Whether to draw a line or arrow between these points, given `trim_offboard_annotations`.
+fn is_annotation_drawn(
+    (p1, p2): ((u8, u8), (u8, u8)),
+    (x_range, y_range): (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> bool {
+    !options.trim_offboard_annotations
+        || [p1, p2]
+            .iter()
+            .all(|(x, y)| x_range.contains(x) && y_range.contains(y))
+}
+
+/// Dash lines according to `options.line_dash`.
+///
+/// The diagram is drawn one unit per board cell, so the dashes scale with the board.
+fn with_line_dash(builder: ElementBuilder, options: &MakeSvgOptions) -> ElementBuilder {
+    match &options.line_dash {
+        Some(dashes) => builder
+            .attr(
+                "stroke-dasharray",
+                dashes
+                    .iter()
+                    .map(|&dash| format_float(dash))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+            .attr("stroke-linejoin", "round"),
+        None => builder,
+    }
+}
+
+/// The class to set on an element, if `options.emit_css_classes` is set.
+fn css_class<'a>(options: &MakeSvgOptions, class: &'a str) -> Option<&'a str> {
+    options.emit_css_classes.then_some(class)
+}
+
+/// With `options.emit_css_classes`, set `class` on `element` and drop the fill and stroke
+/// colors of it and its descendants. `none` is kept since it's part of the shape.
+fn with_css_class(element: Element, class: &str, options: &MakeSvgOptions) -> Element {
+    if !options.emit_css_classes {
+        return element;
+    }
+    let mut element = without_colors(&element);
+    element.set_attr("class", class);
+    element
+}
+
+fn without_colors(element: &Element) -> Element {
+    let mut builder = Element::builder(element.name(), element.ns());
+    for (name, value) in element.attrs() {
+        if (name == "fill" || name == "stroke") && value != "none" {
+            continue;
+        }
+        builder = builder.attr(name, value);
+    }
+    for node in element.nodes() {
+        builder = match node {
+            minidom::Node::Element(child) => builder.append(without_colors(child)),
+            minidom::Node::Text(text) => builder.append(text.as_str()),
+        };
+    }
+    builder.build()
+}
+
+/// Presentation attributes which `extract_style_classes` moves into a stylesheet, with the
+/// unit numeric values need in CSS.
+static STYLE_PROPERTIES: [(&str, &str); 8] = [
+    ("fill", ""),
+    ("fill-opacity", ""),
+    ("opacity", ""),
+    ("stroke", ""),
+    ("stroke-linecap", ""),
+    ("stroke-opacity", ""),
+    ("stroke-width", "px"),
+    ("text-anchor", ""),
+];
+
+/// Move presentation attributes repeated across elements of `svg` into classes defined in a
+/// `<style>` element at its start. Combinations used only once are left inline.
+pub(crate) fn extract_style_classes(svg: &Element) -> Element {
+    fn count_declarations(element: &Element, counts: &mut HashMap<String, usize>) {
+        if let Some(declarations) = style_declarations(element) {
+            *counts.entry(declarations).or_default() += 1;
+        }
+        for child in element.children() {
+            count_declarations(child, counts);
+        }
+    }
+    let mut counts = HashMap::new();
+    count_declarations(svg, &mut counts);
+    let mut repeated: Vec<String> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(declarations, _)| declarations)
+        .collect();
+    repeated.sort_unstable();
+    let classes: HashMap<String, String> = repeated
+        .iter()
+        .enumerate()
+        .map(|(i, declarations)| (declarations.clone(), format!("s{}", i)))
+        .collect();
+    let stylesheet: String = repeated
+        .iter()
+        .map(|declarations| format!(".{}{{{}}}", classes[declarations], declarations))
+        .collect();
+
+    let styled = with_style_classes(svg, &classes);
+    let mut builder = Element::builder(styled.name(), styled.ns());
+    for (name, value) in styled.attrs() {
+        builder = builder.attr(name, value);
+    }
+    builder
+        .append(Element::builder("style", NAMESPACE).append(stylesheet))
+        .append_all(styled.children().cloned())
+        .build()
+}
+
+/// The CSS declarations equivalent to the presentation attributes of `element`, if it has any.
+fn style_declarations(element: &Element) -> Option<String> {
+    let declarations: Vec<String> = STYLE_PROPERTIES
+        .iter()
+        .filter_map(|(name, unit)| {
+            let value = element.attr(name)?;
+            let unit = if value.parse::<f64>().is_ok() {
+                unit
+            } else {
+                ""
+            };
+            Some(format!("{}:{}{}", name, value, unit))
+        })
+        .collect();
+    (!declarations.is_empty()).then(|| declarations.join(";"))
+}
+
+fn with_style_classes(element: &Element, classes: &HashMap<String, String>) -> Element {
+    let class = style_declarations(element).and_then(|declarations| classes.get(&declarations));
+    let mut builder = Element::builder(element.name(), element.ns());
+    for (name, value) in element.attrs() {
+        if class.is_some()
+            && STYLE_PROPERTIES
+                .iter()
+                .any(|(property, _)| *property == name)
+        {
+            continue;
+        }
+        if name == "class" && class.is_some() {
+            continue;
+        }
+        builder = builder.attr(name, value);
+    }
+    if let Some(class) = class {
+        let class = match element.attr("class") {
+            Some(existing) => format!("{} {}", existing, class),
+            None => class.clone(),
+        };
+        builder = builder.attr("class", class);
+    }
+    for node in element.nodes() {
+        builder = match node {
+            minidom::Node::Element(child) => builder.append(with_style_classes(child, classes)),
+            minidom::Node::Text(text) => builder.append(text.as_str()),
+        };
+    }
+    builder.build()
+}
+
+/// Cover each intersection with an invisible square titled with its coordinate.
+///
+/// Viewers show the title as a tooltip when hovering over the point.
+fn build_point_titles_group(goban: &Goban, options: &MakeSvgOptions) -> Element {
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "point-titles")
+        .attr("fill", "transparent");
+    let size = goban.size();
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            let title = Element::builder("title", NAMESPACE)
+                .append(
+                    options
+                        .coordinate_style
+                        .point_label(x, y, size, options.skip_i_column),
+                )
+                .build();
+            group_builder = group_builder.append(
+                Element::builder("rect", NAMESPACE)
+                    .attr("x", format_float(f64::from(x) - 0.5))
+                    .attr("y", format_float(f64::from(y) - 0.5))
+                    .attr("width", "1")
+                    .attr("height", "1")
+                    .append(title),
+            );
+        }
+    }
+    group_builder.build()
+}
+
 /// Draw labels for the provided ranges.
 ///
-/// Assumes lines are a unit apart, offset by `BOARD_MARGIN`.
+/// Assumes lines are a unit apart, offset by `options.board_margin`.
 /// Respects `LABEL_MARGIN`.
-fn draw_board_labels(x_range: Range<u8>, y_range: Range<u8>, options: &MakeSvgOptions) -> Element {
+fn draw_board_labels(
+    x_range: Range<u8>,
+    y_range: Range<u8>,
+    goban_size: (u8, u8),
+    options: &MakeSvgOptions,
+) -> Element {
+    let coordinate_style = options.coordinate_style;
     let (top_margin, _, _, left_margin) = get_margins(&options.label_sides);
     let transform = format!(
         "translate({}, {})",
@@ -502,16 +2644,33 @@ fn draw_board_labels(x_range: Range<u8>, y_range: Range<u8>, options: &MakeSvgOp
         .attr("id", "board-labels")
         .attr("fill", options.style.label_color())
         .attr("transform", transform);
+    let column_label = |x: u8| match options.label_origin {
+        Some((origin_x, _)) => {
+            coordinate_style.column_label_from(x, origin_x, options.skip_i_column)
+        }
+        None => Some(coordinate_style.column_label(x, options.skip_i_column)),
+    };
+    let row_label = |y: u8| match options.label_origin {
+        Some((_, origin_y)) => coordinate_style.row_label_from(y, origin_y),
+        None => Some(coordinate_style.row_label(y, goban_size.1)),
+    };
 
     if options.label_sides.contains(BoardSide::North) {
         let mut builder = Element::builder("g", NAMESPACE).attr("text-anchor", "middle");
         let start = x_range.start;
         for x in x_range.clone() {
+            let label = match column_label(x) {
+                Some(label) => label,
+                None => continue,
+            };
             builder = builder.append(
                 Element::builder("text", NAMESPACE)
-                    .attr("x", format_float(f64::from(x - start) + BOARD_MARGIN))
+                    .attr(
+                        "x",
+                        format_float(f64::from(x - start) + options.board_margin),
+                    )
                     .attr("y", "0")
-                    .append(label_text(x))
+                    .append(label)
                     .build(),
             );
         }
@@ -519,14 +2678,21 @@ fn draw_board_labels(x_range: Range<u8>, y_range: Range<u8>, options: &MakeSvgOp
     };
     if options.label_sides.contains(BoardSide::West) {
         let mut builder = Element::builder("g", NAMESPACE).attr("text-anchor", "end");
-        let end = y_range.end;
-        for y in y_range.clone() {
+        let start = y_range.start;
+        for y in y_range.clone().rev() {
+            let label = match row_label(y) {
+                Some(label) => label,
+                None => continue,
+            };
             builder = builder.append(
                 Element::builder("text", NAMESPACE)
                     .attr("x", "0")
-                    .attr("y", format_float(f64::from(end - y - 1) + BOARD_MARGIN))
+                    .attr(
+                        "y",
+                        format_float(f64::from(y - start) + options.board_margin),
+                    )
                     .attr("dy", "0.35em")
-                    .append(y.to_string())
+                    .append(label)
                     .build(),
             );
         }
@@ -535,14 +2701,22 @@ fn draw_board_labels(x_range: Range<u8>, y_range: Range<u8>, options: &MakeSvgOp
     if options.label_sides.contains(BoardSide::South) {
         let mut builder = Element::builder("g", NAMESPACE).attr("text-anchor", "middle");
         let start = x_range.start;
-        let y = f64::from(y_range.end - y_range.start + 1) - BOARD_MARGIN;
+        // Just past the bottom edge of the board.
+        let y = f64::from(y_range.end - y_range.start - 1) + 2.0 * options.board_margin + 0.08;
         for x in x_range.clone() {
+            let label = match column_label(x) {
+                Some(label) => label,
+                None => continue,
+            };
             builder = builder.append(
                 Element::builder("text", NAMESPACE)
-                    .attr("x", format_float(f64::from(x - start) + BOARD_MARGIN))
+                    .attr(
+                        "x",
+                        format_float(f64::from(x - start) + options.board_margin),
+                    )
                     .attr("y", format_float(y))
                     .attr("alignment-baseline", "hanging")
-                    .append(label_text(x))
+                    .append(label)
                     .build(),
             );
         }
@@ -550,15 +2724,23 @@ fn draw_board_labels(x_range: Range<u8>, y_range: Range<u8>, options: &MakeSvgOp
     };
     if options.label_sides.contains(BoardSide::East) {
         let mut builder = Element::builder("g", NAMESPACE).attr("text-anchor", "start");
-        let end = y_range.end;
-        let x = f64::from(x_range.end - x_range.start + 1) - BOARD_MARGIN;
-        for y in y_range {
+        let start = y_range.start;
+        // Just past the right edge of the board.
+        let x = f64::from(x_range.end - x_range.start - 1) + 2.0 * options.board_margin + 0.08;
+        for y in y_range.rev() {
+            let label = match row_label(y) {
+                Some(label) => label,
+                None => continue,
+            };
             builder = builder.append(
                 Element::builder("text", NAMESPACE)
                     .attr("x", format_float(x))
-                    .attr("y", format_float(f64::from(end - y - 1) + BOARD_MARGIN))
+                    .attr(
+                        "y",
+                        format_float(f64::from(y - start) + options.board_margin),
+                    )
                     .attr("dy", "0.35em")
-                    .append(y.to_string())
+                    .append(label)
                     .build(),
             );
         }
@@ -580,7 +2762,7 @@ fn draw_repeated_stones(
     let width = f64::from(width);
     let (_, _, _, left_margin) = get_margins(&options.label_sides);
     let columns = ((width - 1.0 - (2.0 * entry_padding)) / entry_width).floor() as usize;
-    let x = BOARD_MARGIN
+    let x = options.board_margin
         + left_margin
         + entry_padding
         + (width - 1.0 - 2.0 * entry_padding - entry_width * f64::from(columns as u32)) / 2.0;
@@ -589,7 +2771,7 @@ fn draw_repeated_stones(
         .attr("y", format_float(y))
         .attr("font-size", format_float(entry_height))
         .attr("fill", options.style.line_color()); // TODO: Evaluate this choice
-    let move_number_options = options.move_number_options.unwrap();
+    let move_number_options = options.move_number_options?;
     let repeated_moves: Vec<(u64, u64)> = {
         let mut repeated_moves = Vec::new();
         let mut seen_moves: HashMap<(u8, u8), u64> = HashMap::new();
@@ -607,8 +2789,8 @@ fn draw_repeated_stones(
                             .unwrap_or(true);
                     if n > value && in_range {
                         repeated_moves.push((
-                            n + move_number_options.count_from - move_number_options.start,
-                            value + move_number_options.count_from - move_number_options.start,
+                            move_number_options.display_number(n),
+                            move_number_options.display_number(value),
                         ));
                     }
                 }
@@ -642,7 +2824,7 @@ fn draw_repeated_stones(
                 .attr("fill", "white")
                 .attr("stroke", options.style.line_color())
                 .attr("stroke-width", format_float(options.style.line_width()))
-                .attr("x", format_float(BOARD_MARGIN + left_margin))
+                .attr("x", format_float(options.board_margin + left_margin))
                 .attr("y", format_float(diagram_height))
                 .attr("width", format_float(width - 1.0))
                 .attr("height", format_float(rect_height)),
@@ -653,19 +2835,300 @@ fn draw_repeated_stones(
     Some((group, rect_height))
 }
 
-fn label_text(x: u8) -> String {
-    if x + b'A' < b'I' {
-        ((x + b'A') as char).to_string()
-    } else {
-        ((x + b'B') as char).to_string() // skip 'I'
+/// Draw text wrapped to the width of the diagram starting at `y`.
+///
+/// Returns the caption element and its height.
+fn draw_caption(
+    text: &str,
+    id: &str,
+    diagram_width: f64,
+    y: f64,
+    options: &MakeSvgOptions,
+) -> (Element, f64) {
+    let line_height = options.font_size * CAPTION_LINE_HEIGHT;
+    let max_chars = ((diagram_width - 2.0 * options.board_margin)
+        / (options.font_size * CHARACTER_WIDTH))
+        .max(1.0) as usize;
+    let lines = wrap_text(text, max_chars);
+    let mut text_builder = Element::builder("text", NAMESPACE)
+        .attr("y", format_float(y + options.font_size))
+        .attr("fill", options.style.label_color());
+    for (i, line) in lines.iter().enumerate() {
+        let mut tspan_builder = Element::builder("tspan", NAMESPACE)
+            .attr("x", format_float(options.board_margin))
+            .append(line.as_str());
+        if i != 0 {
+            tspan_builder = tspan_builder.attr("dy", format_float(line_height));
+        }
+        text_builder = text_builder.append(tspan_builder);
+    }
+    let group = Element::builder("g", NAMESPACE)
+        .attr("id", id)
+        .append(text_builder)
+        .build();
+    let height = options.font_size + line_height * (lines.len().max(1) - 1) as f64;
+
+    (group, height)
+}
+
+/// Draw the comments on numbered moves as a list in a column to the right of the diagram.
+///
+/// Returns the list element and its height.
+fn draw_comment_list(
+    goban: &Goban,
+    diagram_width: f64,
+    options: &MakeSvgOptions,
+) -> (Element, f64) {
+    let line_height = options.font_size * CAPTION_LINE_HEIGHT;
+    let max_chars = ((COMMENT_LIST_WIDTH - options.board_margin)
+        / (options.font_size * CHARACTER_WIDTH))
+        .max(1.0) as usize;
+    let lines: Vec<String> = goban
+        .move_comments()
+        .iter()
+        .flat_map(|(move_number, comment)| {
+            wrap_text(&format!("{}. {}", move_number, comment), max_chars)
+        })
+        .collect();
+    let mut text_builder = Element::builder("text", NAMESPACE)
+        .attr("y", format_float(options.board_margin + options.font_size))
+        .attr("fill", options.style.label_color());
+    for (i, line) in lines.iter().enumerate() {
+        let mut tspan_builder = Element::builder("tspan", NAMESPACE)
+            .attr("x", format_float(diagram_width))
+            .append(line.as_str());
+        if i != 0 {
+            tspan_builder = tspan_builder.attr("dy", format_float(line_height));
+        }
+        text_builder = text_builder.append(tspan_builder);
+    }
+    let group = Element::builder("g", NAMESPACE)
+        .attr("id", "comment-list")
+        .append(text_builder)
+        .build();
+    let height =
+        options.board_margin + options.font_size + line_height * (lines.len().max(1) - 1) as f64;
+
+    (group, height)
+}
+
+/// Draw a key with a row for each kind of markup shown on `goban`, starting at `y`.
+///
+/// Returns `None` if no markup is shown.
+fn draw_legend(goban: &Goban, y: f64, options: &MakeSvgOptions) -> Option<(Element, f64)> {
+    let kinds: Vec<MarkupKind> = MarkupKind::ALL
+        .iter()
+        .copied()
+        .filter(|kind| kind.is_shown(goban, options))
+        .collect();
+    if kinds.is_empty() {
+        return None;
+    }
+    let mut group_builder = Element::builder("g", NAMESPACE).attr("id", "legend");
+    for (i, kind) in kinds.iter().enumerate() {
+        let row_center = y + LEGEND_ROW_HEIGHT * (i as f64 + 0.5);
+        let label = options
+            .legend_labels
+            .get(kind)
+            .map(String::as_str)
+            .unwrap_or_else(|| kind.default_label());
+        group_builder = group_builder
+            .append(
+                Element::builder("g", NAMESPACE)
+                    .attr(
+                        "transform",
+                        format!(
+                            "translate({}, {}) scale(0.8)",
+                            format_float(options.board_margin + 0.3),
+                            format_float(row_center)
+                        ),
+                    )
+                    .append(kind.draw_symbol(&options.style)),
+            )
+            .append(
+                Element::builder("text", NAMESPACE)
+                    .attr("x", format_float(options.board_margin + 0.9))
+                    .attr("y", format_float(row_center))
+                    .attr("dy", "0.35em")
+                    .attr("fill", options.style.label_color())
+                    .append(label),
+            );
+    }
+    Some((
+        group_builder.build(),
+        LEGEND_ROW_HEIGHT * kinds.len() as f64,
+    ))
+}
+
+/// Draw `result` in a badge inset in a corner of the drawn range.
+fn draw_result_stamp(
+    result: &str,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
+    let (x_range, y_range) = ranges;
+    let padding = 0.15;
+    let inset = 0.1;
+    let width = result.chars().count() as f64 * options.font_size * CHARACTER_WIDTH + 2.0 * padding;
+    let height = options.font_size + 2.0 * padding;
+    let left = f64::from(x_range.start) - 0.5 + inset;
+    let right = f64::from(x_range.end) - 0.5 - inset - width;
+    let top = f64::from(y_range.start) - 0.5 + inset;
+    let bottom = f64::from(y_range.end) - 0.5 - inset - height;
+    let (x, y) = match options.result_stamp_corner {
+        BoardCorner::TopLeft => (left, top),
+        BoardCorner::TopRight => (right, top),
+        BoardCorner::BottomLeft => (left, bottom),
+        BoardCorner::BottomRight => (right, bottom),
+    };
+    Element::builder("g", NAMESPACE)
+        .attr("id", "result-stamp")
+        .append(
+            Element::builder("rect", NAMESPACE)
+                .attr("x", format_float(x))
+                .attr("y", format_float(y))
+                .attr("width", format_float(width))
+                .attr("height", format_float(height))
+                .attr("rx", format_float(padding))
+                .attr("fill", options.style.label_color())
+                .attr("fill-opacity", "0.9"),
+        )
+        .append(
+            Element::builder("text", NAMESPACE)
+                .attr("x", format_float(x + width / 2.0))
+                .attr("y", format_float(y + height / 2.0))
+                .attr("dy", "0.35em")
+                .attr("text-anchor", "middle")
+                .attr("fill", options.style.background_fill())
+                .append(result),
+        )
+        .build()
+}
+
+/// Draw a table of the game's komi, handicap, ruleset and board size, leaving out rows for
+/// properties the game doesn't have.
+fn draw_info_box(
+    game_info: &GameInfo,
+    ranges: (&Range<u8>, &Range<u8>),
+    options: &MakeSvgOptions,
+) -> Element {
+    let (x_range, y_range) = ranges;
+    let mut rows = vec![];
+    if let Some(komi) = game_info.komi {
+        rows.push(("Komi", komi.to_string()));
+    }
+    if let Some(handicap) = game_info.handicap {
+        rows.push(("Handicap", handicap.to_string()));
+    }
+    if let Some(ruleset) = game_info.ruleset.as_deref().filter(|s| !s.is_empty()) {
+        rows.push(("Rules", ruleset.to_string()));
     }
+    let (board_width, board_height) = game_info.board_size;
+    rows.push(("Size", format!("{}×{}", board_width, board_height)));
+
+    let padding = 0.15;
+    let inset = 0.1;
+    let row_height = options.font_size * CAPTION_LINE_HEIGHT;
+    let chars = rows
+        .iter()
+        .map(|(name, value)| name.chars().count() + 1 + value.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = chars as f64 * options.font_size * CHARACTER_WIDTH + 2.0 * padding;
+    let height = rows.len() as f64 * row_height + 2.0 * padding;
+    let left = f64::from(x_range.start) - 0.5 + inset;
+    let right = f64::from(x_range.end) - 0.5 - inset - width;
+    let top = f64::from(y_range.start) - 0.5 + inset;
+    let bottom = f64::from(y_range.end) - 0.5 - inset - height;
+    let (x, y) = match options.info_box_corner {
+        BoardCorner::TopLeft => (left, top),
+        BoardCorner::TopRight => (right, top),
+        BoardCorner::BottomLeft => (left, bottom),
+        BoardCorner::BottomRight => (right, bottom),
+    };
+    let mut group_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "info-box")
+        .attr("fill", options.style.label_color())
+        .append(
+            Element::builder("rect", NAMESPACE)
+                .attr("x", format_float(x))
+                .attr("y", format_float(y))
+                .attr("width", format_float(width))
+                .attr("height", format_float(height))
+                .attr("rx", format_float(padding))
+                .attr("fill", options.style.background_fill())
+                .attr("stroke", options.style.line_color())
+                .attr("stroke-width", format_float(options.style.line_width())),
+        );
+    for (i, (name, value)) in rows.into_iter().enumerate() {
+        let row_y = format_float(y + padding + (i as f64 + 0.5) * row_height);
+        group_builder = group_builder
+            .append(
+                Element::builder("text", NAMESPACE)
+                    .attr("x", format_float(x + padding))
+                    .attr("y", row_y.as_str())
+                    .attr("dy", "0.35em")
+                    .append(name),
+            )
+            .append(
+                Element::builder("text", NAMESPACE)
+                    .attr("x", format_float(x + width - padding))
+                    .attr("y", row_y.as_str())
+                    .attr("dy", "0.35em")
+                    .attr("text-anchor", "end")
+                    .append(value),
+            );
+    }
+    group_builder.build()
+}
+
+/// Greedily wrap text into lines of at most `max_chars` characters.
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            // Hard break any words too long to fit on a line.
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if line.is_empty() {
+                line = word;
+            } else if line.chars().count() + 1 + word.chars().count() <= max_chars {
+                line.push(' ');
+                line.push_str(&word);
+            } else {
+                lines.push(std::mem::replace(&mut line, word));
+            }
+        }
+        lines.push(line);
+    }
+    lines
 }
 
-fn draw_stone(stone: Stone, style: &GobanStyle) -> Element {
+fn draw_stone(stone: Stone, options: &MakeSvgOptions) -> Element {
+    let style = &options.style;
     let mut circle_builder = Element::builder("circle", NAMESPACE)
         .attr("cx", stone.x)
         .attr("cy", stone.y)
-        .attr("r", "0.48");
+        .attr("r", format_float(options.stone_radius));
+    if options.stone_variation {
+        // Rotating the stone about its center moves any gradient highlight.
+        circle_builder = circle_builder.attr(
+            "transform",
+            format!(
+                "rotate({}, {}, {})",
+                stone_variation_angle(stone.x, stone.y),
+                stone.x,
+                stone.y
+            ),
+        );
+    }
     if let Some(stroke) = style.stone_stroke(stone.color) {
         circle_builder = circle_builder
             .attr("stroke", stroke)
@@ -674,33 +3137,78 @@ fn draw_stone(stone: Stone, style: &GobanStyle) -> Element {
     if let Some(fill) = style.stone_fill(stone.color) {
         circle_builder = circle_builder.attr("fill", fill);
     }
+    if is_ghosted(stone.color, options) {
+        circle_builder = circle_builder.attr("opacity", format_float(GHOST_STONE_OPACITY));
+    }
     circle_builder.build()
 }
 
+/// Whether anything is drawn on empty points which numbers and labels there need hiding from.
+fn is_grid_drawn(options: &MakeSvgOptions) -> bool {
+    options.draw_grid_lines || options.draw_hoshi || options.draw_all_points
+}
+
+fn is_ghosted(color: StoneColor, options: &MakeSvgOptions) -> bool {
+    match color {
+        StoneColor::Black => options.ghost_black,
+        StoneColor::White => options.ghost_white,
+    }
+}
+
+/// Pseudo-random angle in [-30, 30] degrees, stable for a given point.
+fn stone_variation_angle(x: u8, y: u8) -> i32 {
+    let hash = (u32::from(x) * 7919 + u32::from(y) * 104729) ^ 0x5bd1;
+    (hash % 61) as i32 - 30
+}
+
 fn draw_move_number(
     x: u8,
     y: u8,
-    n: u64,
+    text: &str,
     color: Option<StoneColor>,
-    style: &GobanStyle,
+    fill: &str,
+    options: &MakeSvgOptions,
 ) -> Element {
-    // let text = svg::node::Text::new(n.to_string());
-    let text_element = Element::builder("text", NAMESPACE)
+    let mut text_builder = Element::builder("text", NAMESPACE)
         .attr("x", x)
         .attr("y", y)
         .attr("dy", "0.35em")
-        .attr("fill", style.markup_color(color))
-        .append(n.to_string());
+        .attr("fill", fill);
+    if options.move_number_halo {
+        if let Some(color) = color {
+            // Outline the digits in the stone's own color, so they stay legible where they
+            // spill off the stone.
+            text_builder = text_builder
+                .attr("stroke", color.color())
+                .attr("stroke-width", "0.08")
+                .attr("paint-order", "stroke");
+        }
+    }
+    let text_element = text_builder.append(text);
     let mut group_builder = Element::builder("g", NAMESPACE);
-    if color.is_none() {
-        group_builder = group_builder.append(
-            Element::builder("rect", NAMESPACE)
-                .attr("fill", style.background_fill())
-                .attr("x", format_float(f64::from(x) - 0.4))
-                .attr("y", format_float(f64::from(y) - 0.4))
-                .attr("width", "0.8")
-                .attr("height", "0.8"),
-        );
+    if color.is_none() && is_grid_drawn(options) {
+        // Hide the lines behind the number with a shape slightly smaller than a stone.
+        let half_width = options.stone_radius * 5.0 / 6.0;
+        match options.move_number_background {
+            BackgroundShape::Rectangle => {
+                group_builder = group_builder.append(
+                    with_intersection_fill(Element::builder("rect", NAMESPACE), options)
+                        .attr("x", format_float(f64::from(x) - half_width))
+                        .attr("y", format_float(f64::from(y) - half_width))
+                        .attr("width", format_float(2.0 * half_width))
+                        .attr("height", format_float(2.0 * half_width)),
+                );
+            }
+            BackgroundShape::Circle => {
+                group_builder = group_builder.append(
+                    with_intersection_fill(Element::builder("circle", NAMESPACE), options)
+                        .attr("cx", x)
+                        .attr("cy", y)
+                        .attr("r", format_float(half_width)),
+                );
+            }
+            BackgroundShape::None => {}
+        }
     }
 
     group_builder.append(text_element).build()
@@ -793,6 +3301,22 @@ fn draw_selected(x: u8, y: u8, color: Option<StoneColor>, style: &GobanStyle) ->
         .build()
 }
 
+/// Draw a small square filled with the owner's color.
+///
+/// The square is outlined in the contrasting color so it stands out against the board.
+fn draw_territory(x: u8, y: u8, color: StoneColor, style: &GobanStyle) -> Element {
+    let width = 0.3;
+    Element::builder("rect", NAMESPACE)
+        .attr("x", format_float(f64::from(x) - 0.5 * width))
+        .attr("y", format_float(f64::from(y) - 0.5 * width))
+        .attr("width", format_float(width))
+        .attr("height", format_float(width))
+        .attr("fill", color.color())
+        .attr("stroke", color.contrast_color())
+        .attr("stroke-width", format_float(style.line_width()))
+        .build()
+}
+
 fn dim_square(x: u8, y: u8) -> Element {
     Element::builder("g", NAMESPACE)
         .attr("stroke", "none")
@@ -809,20 +3333,47 @@ fn dim_square(x: u8, y: u8) -> Element {
         .build()
 }
 
-fn draw_label(x: u8, y: u8, text: &str, color: Option<StoneColor>, style: &GobanStyle) -> Element {
-    let text = text.chars().take(2).collect::<String>();
-    let text_element = Element::builder("text", NAMESPACE)
+fn draw_label(
+    x: u8,
+    y: u8,
+    text: &str,
+    color: Option<StoneColor>,
+    options: &MakeSvgOptions,
+) -> Element {
+    let style = &options.style;
+    let fill = if options.colorize_labels {
+        label_color(text)
+    } else {
+        match color {
+            Some(color) => color.contrast_color(),
+            None => style.markup_color(None),
+        }
+    };
+    let is_truncated = text.chars().count() > options.max_label_chars;
+    let mut text = text
+        .chars()
+        .take(options.max_label_chars)
+        .collect::<String>();
+    if is_truncated && options.label_ellipsis {
+        text.push('…');
+    }
+    let text_length = text.chars().count();
+    let mut text_builder = Element::builder("text", NAMESPACE)
         .attr("x", x)
         .attr("y", y)
         .attr("text-anchor", "middle")
         .attr("dy", "0.35em")
-        .attr("fill", style.markup_color(color))
-        .append(text);
+        .attr("fill", fill);
+    if text_length > 2 {
+        // Shrink longer labels to fit in the space of two characters.
+        let font_size = options.font_size * 2.0 / text_length as f64;
+        text_builder = text_builder.attr("font-size", format_float(font_size));
+    }
+    let text_element = text_builder.append(text);
     let mut group_builder = Element::builder("g", NAMESPACE);
-    if color.is_none() {
+    if color.is_none() && is_grid_drawn(options) {
         group_builder = group_builder.append(
-            Element::builder("rect", NAMESPACE)
-                .attr("fill", style.background_fill())
+            with_intersection_fill(Element::builder("rect", NAMESPACE), options)
                 .attr("x", format_float(f64::from(x) - 0.4))
                 .attr("y", format_float(f64::from(y) - 0.4))
                 .attr("width", "0.8")
@@ -833,6 +3384,25 @@ fn draw_label(x: u8, y: u8, text: &str, color: Option<StoneColor>, style: &Goban
     group_builder.append(text_element).build()
 }
 
+/// Fill a patch hiding the grid behind a number or label to match the board. On textured
+/// boards it's partly transparent so the grain shows through.
+fn with_intersection_fill(builder: ElementBuilder, options: &MakeSvgOptions) -> ElementBuilder {
+    match options.board_texture {
+        Some(texture) => builder
+            .attr("fill", texture.base_color())
+            .attr("fill-opacity", format_float(TEXTURED_PATCH_OPACITY)),
+        None => builder.attr("fill", options.style.intersection_fill()),
+    }
+}
+
+/// A color from `LABEL_PALETTE` for `text`, using FNV-1a so it's stable across builds.
+fn label_color(text: &str) -> &'static str {
+    let hash = text.bytes().fold(0x811c9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    });
+    LABEL_PALETTE[hash as usize % LABEL_PALETTE.len()]
+}
+
 fn get_margins(label_sides: &BoardSideSet) -> (f64, f64, f64, f64) {
     let top = if label_sides.contains(BoardSide::North) {
         LABEL_MARGIN
@@ -857,9 +3427,94 @@ fn get_margins(label_sides: &BoardSideSet) -> (f64, f64, f64, f64) {
     (top, right, bottom, left)
 }
 
-fn format_float(x: f64) -> String {
-    format!("{:.4}", x)
-        .trim_end_matches('0')
-        .trim_end_matches('.')
-        .to_string()
+pub(crate) fn format_float(x: f64) -> String {
+    format_float_with_precision(x, 4)
+}
+
+fn format_float_with_precision(x: f64, precision: u8) -> String {
+    let formatted = format!("{:.*}", usize::from(precision), x);
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    match formatted {
+        "-0" => "0".to_string(),
+        _ => formatted.to_string(),
+    }
+}
+
+/// Attributes holding positions on the board, which are all `coordinate_precision` rounds.
+/// Sizes, opacities, offsets in `em` and the diagram's scale could be shrunk to nothing or
+/// thrown off by rounding, so they're left alone.
+static COORDINATE_ATTRIBUTES: [&str; 12] = [
+    "x", "y", "x1", "y1", "x2", "y2", "cx", "cy", "width", "height", "points", "d",
+];
+
+/// Rewrite every decimal number in the coordinate attributes of `element` and its descendants,
+/// including translations in `transform`, with at most `precision` decimal places.
+fn with_rounded_numbers(element: &Element, precision: u8) -> Element {
+    let mut builder = Element::builder(element.name(), element.ns());
+    for (name, value) in element.attrs() {
+        builder = if COORDINATE_ATTRIBUTES.contains(&name) {
+            builder.attr(name, round_numbers(value, precision))
+        } else if name == "transform" {
+            builder.attr(name, round_translations(value, precision))
+        } else {
+            builder.attr(name, value)
+        };
+    }
+    for node in element.nodes() {
+        builder = match node {
+            minidom::Node::Element(child) => builder.append(with_rounded_numbers(child, precision)),
+            minidom::Node::Text(text) => builder.append(text.as_str()),
+        };
+    }
+    builder.build()
+}
+
+/// Round the arguments of each `translate(...)` in a transform list, leaving scales and
+/// rotations as they are.
+fn round_translations(transform: &str, precision: u8) -> String {
+    transform
+        .split_inclusive(')')
+        .map(|function| {
+            if function.trim_start().starts_with("translate(") {
+                round_numbers(function, precision)
+            } else {
+                function.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Round the decimal numbers in `value`, leaving integers alone. Anything else, like the
+/// command letters in path data, separates numbers.
+fn round_numbers(value: &str, precision: u8) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut rounded = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let is_number_start = chars[i].is_ascii_digit()
+            || (matches!(chars[i], '-' | '.')
+                && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()));
+        if !is_number_start {
+            rounded.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+            i += 1;
+        }
+        let token: String = chars[start..i].iter().collect();
+        match token.parse::<f64>() {
+            Ok(number) if token.contains('.') => {
+                rounded.push_str(&format_float_with_precision(number, precision))
+            }
+            _ => rounded.push_str(&token),
+        }
+    }
+    rounded
 }
@@ -0,0 +1,168 @@
+use minidom::Element;
+use sgf_parse::{go, SgfNode};
+
+use crate::errors::MakeSvgError;
+use crate::goban::StoneColor;
+use crate::make_svg::{format_float, MakeSvgOptions, SizeSpec, NAMESPACE};
+use crate::parse::parse_go;
+
+static NODE_RADIUS: f64 = 0.3;
+static EMPTY_NODE_RADIUS: f64 = 0.15;
+static EDGE_WIDTH: f64 = 0.05;
+
+struct TreeNode {
+    column: usize,
+    row: usize,
+    color: Option<StoneColor>,
+}
+
+/// Render the variation tree of the selected game.
+///
+/// Each node is drawn as a circle in the color of the player who moved, with the main variation
+/// along the top row and each other variation on its own row below. Columns are node depths.
+pub fn make_tree_svg(sgf: &str, options: &MakeSvgOptions) -> Result<Element, MakeSvgError> {
+    options.validate()?;
+    let collection = parse_go(sgf)?;
+    if collection.is_empty() {
+        return Err(MakeSvgError::InsufficientSgfNodes);
+    }
+    let root = collection
+        .get(options.game_index)
+        .ok_or(MakeSvgError::MissingGame)?;
+    let (nodes, edges) = layout_tree(root);
+    let columns = nodes.iter().map(|node| node.column + 1).max().unwrap_or(1);
+    let rows = nodes.iter().map(|node| node.row + 1).max().unwrap_or(1);
+
+    let mut edges_builder = Element::builder("g", NAMESPACE)
+        .attr("id", "edges")
+        .attr("stroke", options.style.line_color())
+        .attr("stroke-width", format_float(EDGE_WIDTH));
+    for (parent, child) in edges {
+        let (parent, child) = (&nodes[parent], &nodes[child]);
+        edges_builder = edges_builder.append(
+            Element::builder("line", NAMESPACE)
+                .attr("x1", format_float(parent.column as f64 + 0.5))
+                .attr("y1", format_float(parent.row as f64 + 0.5))
+                .attr("x2", format_float(child.column as f64 + 0.5))
+                .attr("y2", format_float(child.row as f64 + 0.5)),
+        );
+    }
+
+    let mut nodes_builder = Element::builder("g", NAMESPACE).attr("id", "nodes");
+    for node in &nodes {
+        let mut circle_builder = Element::builder("circle", NAMESPACE)
+            .attr("cx", format_float(node.column as f64 + 0.5))
+            .attr("cy", format_float(node.row as f64 + 0.5));
+        circle_builder = match node.color {
+            Some(color) => {
+                let default_fill = match color {
+                    StoneColor::Black => "black",
+                    StoneColor::White => "white",
+                };
+                circle_builder
+                    .attr("r", format_float(NODE_RADIUS))
+                    .attr(
+                        "fill",
+                        options.style.stone_fill(color).unwrap_or(default_fill),
+                    )
+                    .attr(
+                        "stroke",
+                        options
+                            .style
+                            .stone_stroke(color)
+                            .unwrap_or(options.style.line_color()),
+                    )
+                    .attr("stroke-width", format_float(EDGE_WIDTH))
+            }
+            None => circle_builder
+                .attr("r", format_float(EMPTY_NODE_RADIUS))
+                .attr("fill", options.style.line_color()),
+        };
+        nodes_builder = nodes_builder.append(circle_builder);
+    }
+
+    let scale = match options.size {
+        SizeSpec::TotalWidth(width) => width / columns as f64,
+        SizeSpec::CellSize(cell_size) => cell_size,
+    };
+    let viewbox_width = columns as f64 * scale;
+    let diagram = Element::builder("g", NAMESPACE)
+        .attr("id", "tree")
+        .attr("transform", format!("scale({})", format_float(scale)))
+        .append(edges_builder)
+        .append(nodes_builder)
+        .build();
+    let background = Element::builder("rect", NAMESPACE)
+        .attr("fill", options.style.background_fill())
+        .attr("height", "100%")
+        .attr("width", "100%")
+        .attr("x", "0")
+        .attr("y", "0")
+        .build();
+    let viewbox_height = rows as f64 * scale;
+    let svg = Element::builder("svg", NAMESPACE)
+        .attr(
+            "viewBox",
+            format!(
+                "0 0 {} {}",
+                format_float(viewbox_width),
+                format_float(viewbox_height)
+            ),
+        )
+        .attr("width", viewbox_width.to_string())
+        .append(
+            Element::builder("defs", NAMESPACE)
+                .append_all(options.style.defs()?)
+                .build(),
+        )
+        .append(background)
+        .append(diagram)
+        .build();
+    Ok(svg)
+}
+
+/// Assign each node a column (its depth) and a row, returning the nodes and parent-child edges
+/// as pairs of indices.
+///
+/// The first child continues its parent's row; every other child starts a new row below all
+/// rows used so far, so variations never overlap.
+fn layout_tree(root: &SgfNode<go::Prop>) -> (Vec<TreeNode>, Vec<(usize, usize)>) {
+    let mut nodes: Vec<TreeNode> = vec![];
+    let mut edges = vec![];
+    let mut next_row = 0;
+    // (node, depth, parent index, whether it's the first child)
+    let mut to_process: Vec<(&SgfNode<go::Prop>, usize, Option<usize>, bool)> =
+        vec![(root, 0, None, true)];
+    while let Some((sgf_node, column, parent, is_first_child)) = to_process.pop() {
+        let row = match parent {
+            Some(parent) if is_first_child => nodes[parent].row,
+            _ if nodes.is_empty() => 0,
+            _ => {
+                next_row += 1;
+                next_row
+            }
+        };
+        let index = nodes.len();
+        nodes.push(TreeNode {
+            column,
+            row,
+            color: move_color(sgf_node),
+        });
+        if let Some(parent) = parent {
+            edges.push((parent, index));
+        }
+        // Push in reverse so the first child is laid out first.
+        for (i, child) in sgf_node.children.iter().enumerate().rev() {
+            to_process.push((child, column + 1, Some(index), i == 0));
+        }
+    }
+    (nodes, edges)
+}
+
+fn move_color(sgf_node: &SgfNode<go::Prop>) -> Option<StoneColor> {
+    sgf_node.properties().find_map(|prop| match prop {
+        go::Prop::B(_) => Some(StoneColor::Black),
+        go::Prop::W(_) => Some(StoneColor::White),
+        _ => None,
+    })
+}
@@ -0,0 +1,47 @@
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::ColorType;
+use minidom::Element;
+
+use crate::errors::MakeSvgError;
+use crate::make_png::rasterize;
+use crate::make_svg::{make_svg, MakeSvgOptions};
+
+/// Render a WebP of the selected node.
+///
+/// Images are lossily encoded with `quality` from 0 to 100. `dpi` works as for `make_png`.
+pub fn make_webp(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    dpi: f64,
+    quality: f32,
+) -> Result<Vec<u8>, MakeSvgError> {
+    let svg = make_svg(sgf, options)?;
+    svg_to_webp(&svg, dpi, quality)
+}
+
+/// Rasterize an svg generated by `make_svg` to WebP bytes. See `make_webp`.
+pub fn svg_to_webp(svg: &Element, dpi: f64, quality: f32) -> Result<Vec<u8>, MakeSvgError> {
+    if !(0.0..=100.0).contains(&quality) {
+        return Err(MakeSvgError::WebpError(
+            "Quality must be between 0 and 100".into(),
+        ));
+    }
+    let pixmap = rasterize(svg, dpi)?;
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    let mut buffer = vec![];
+    // image deprecates lossy encoding, but it's the point of WebP output here.
+    #[allow(deprecated)]
+    let encoder =
+        WebPEncoder::new_with_quality(&mut buffer, WebPQuality::lossy(quality.round() as u8));
+    encoder
+        .encode(&data, pixmap.width(), pixmap.height(), ColorType::Rgba8)
+        .map_err(|e| MakeSvgError::WebpError(e.into()))?;
+    Ok(buffer)
+}
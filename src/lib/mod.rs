@@ -1,12 +1,71 @@
 mod args;
 mod board_side;
+mod board_transform;
+mod coordinates;
 mod errors;
+mod game_info;
 mod generated_styles;
+mod gib;
 mod goban;
 mod goban_range;
 mod goban_style;
+mod handicap;
+#[cfg(feature = "gif")]
+mod make_animation;
+mod make_ascii;
+#[cfg(feature = "batch")]
+mod make_batch;
+mod make_comparison;
+mod make_figure_files;
+#[cfg(feature = "json")]
+mod make_json;
+#[cfg(feature = "pdf")]
+mod make_pdf;
+#[cfg(feature = "png")]
+mod make_png;
 mod make_svg;
+mod make_tree;
+#[cfg(feature = "webp")]
+mod make_webp;
 mod node_description;
+mod parse;
+#[cfg(feature = "gzip")]
+mod read_input;
+mod render;
 
-pub use args::{MakeSvgArgs, OutputFormat, SgfRenderArgs};
-pub use make_svg::make_svg;
+pub use args::{InputFormat, MakeSvgArgs, OutputFormat, SgfRenderArgs};
+pub use board_transform::BoardTransform;
+pub use coordinates::CoordinateStyle;
+pub use errors::MakeSvgError;
+pub use game_info::{game_info, GameInfo, ResultFormat};
+pub use gib::gib_to_sgf;
+pub use goban::{game_length, game_positions, Goban, MarkupSet, Stone, StoneColor};
+pub use handicap::handicap_points;
+pub use make_ascii::make_ascii;
+pub use make_comparison::make_comparison_svg;
+pub use make_figure_files::render_figures_to_files;
+pub use make_svg::{
+    make_figures, make_layered_svg, make_svg, make_svg_from_collection, make_svg_sequence,
+    make_svg_to_writer, render_changed_region, render_with_move_table, ArrowStyle, BackgroundShape,
+    BoardCorner, BoardTexture, MakeSvgOptions, MarkupKind, MoveNumberDisplay, MoveNumberOptions,
+    ShapeRendering, SizeSpec, SolutionKind,
+};
+pub use make_tree::make_tree_svg;
+pub use node_description::{NodeDescription, NodePathStep};
+pub use parse::ParseErrorLocation;
+pub use render::{render, render_with_settings, FormatSettings};
+
+#[cfg(feature = "gif")]
+pub use make_animation::{make_animation, MAX_ANIMATION_FRAMES};
+#[cfg(feature = "batch")]
+pub use make_batch::make_svg_batch;
+#[cfg(feature = "json")]
+pub use make_json::make_json;
+#[cfg(feature = "pdf")]
+pub use make_pdf::{make_pdf, svg_to_pdf};
+#[cfg(feature = "png")]
+pub use make_png::{make_png, svg_to_png, BASE_DPI};
+#[cfg(feature = "webp")]
+pub use make_webp::{make_webp, svg_to_webp};
+#[cfg(feature = "gzip")]
+pub use read_input::{make_svg_from_reader, read_sgf};
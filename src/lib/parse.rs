@@ -0,0 +1,164 @@
+use std::ops::Range;
+
+use sgf_parse::{go, SgfNode, SgfParseError};
+
+use crate::errors::MakeSvgError;
+
+/// Characters of context to include on either side of a parse error.
+static SNIPPET_RADIUS: usize = 10;
+
+/// Where in the input an SGF parse error occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrorLocation {
+    /// Byte offset of the start of the offending token.
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, in characters.
+    pub column: usize,
+    /// The input around the error.
+    pub snippet: String,
+}
+
+impl std::fmt::Display for ParseErrorLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (near {:?})",
+            self.line, self.column, self.snippet
+        )
+    }
+}
+
+impl ParseErrorLocation {
+    fn new(text: &str, offset: usize) -> Self {
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = text[line_start..offset].chars().count() + 1;
+        let snippet_start = before
+            .char_indices()
+            .rev()
+            .nth(SNIPPET_RADIUS - 1)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let snippet_end = text[offset..]
+            .char_indices()
+            .nth(SNIPPET_RADIUS)
+            .map(|(i, _)| offset + i)
+            .unwrap_or(text.len());
+        ParseErrorLocation {
+            offset,
+            line,
+            column,
+            snippet: text[snippet_start..snippet_end].to_string(),
+        }
+    }
+}
+
+/// Parse an SGF collection, reporting where in `sgf` any parse error occurred.
+pub(crate) fn parse_go(sgf: &str) -> Result<Vec<SgfNode<go::Prop>>, MakeSvgError> {
+    go::parse(sgf).map_err(|error| {
+        MakeSvgError::ParseError(
+            error,
+            Some(ParseErrorLocation::new(sgf, error_offset(sgf, error))),
+        )
+    })
+}
+
+/// Find the byte offset of the token which caused `error` when parsing `sgf`.
+///
+/// `sgf_parse` doesn't report positions, so this reparses prefixes of the input ending at
+/// successive tokens, with any open game trees closed. Parsing stops at the first bad token, so
+/// once a prefix includes it every longer prefix fails the same way and a binary search finds
+/// it. Errors which no prefix reproduces, like unclosed game trees, are reported at the end of
+/// the input.
+fn error_offset(sgf: &str, error: SgfParseError) -> usize {
+    let (tokens, lexer_failure) = token_spans(sgf);
+    if let SgfParseError::LexerError(_) = error {
+        return lexer_failure.unwrap_or(sgf.len());
+    }
+    // Each token's span along with the text needed to close its prefix.
+    let mut depth = 0;
+    let prefixes: Vec<(Range<usize>, String)> = tokens
+        .into_iter()
+        .map(|(kind, span)| {
+            let mut ending = String::new();
+            match kind {
+                TokenKind::StartGameTree => {
+                    depth += 1;
+                    // Give the tree a root node so it's only invalid if it's misplaced.
+                    ending.push(';');
+                }
+                TokenKind::EndGameTree => depth = usize::max(depth, 1) - 1,
+                TokenKind::Other => {}
+            }
+            ending.push_str(&")".repeat(depth));
+            (span, ending)
+        })
+        .collect();
+    let index = prefixes.partition_point(|(span, ending)| {
+        let prefix = format!("{}{}", &sgf[..span.end], ending);
+        go::parse(&prefix).err() != Some(error)
+    });
+    prefixes
+        .get(index)
+        .map(|(span, _)| span.start)
+        .unwrap_or(sgf.len())
+}
+
+enum TokenKind {
+    StartGameTree,
+    EndGameTree,
+    Other,
+}
+
+/// Split `text` into tokens the same way `sgf_parse` does, returning their spans along with
+/// the offset of the token which failed to tokenize, if any.
+fn token_spans(text: &str) -> (Vec<(TokenKind, Range<usize>)>, Option<usize>) {
+    let mut tokens = vec![];
+    let mut cursor = 0;
+    let skip_whitespace = |cursor: &mut usize| {
+        while text[*cursor..].starts_with(|c: char| c.is_ascii_whitespace()) {
+            *cursor += 1;
+        }
+    };
+    while let Some(c) = text[cursor..].chars().next() {
+        let start = cursor;
+        let kind = match c {
+            '(' => TokenKind::StartGameTree,
+            ')' => TokenKind::EndGameTree,
+            _ => TokenKind::Other,
+        };
+        if let '(' | ')' | ';' = c {
+            cursor += 1;
+        } else {
+            // A property: an ascii identifier followed by any number of bracketed values.
+            loop {
+                match text[cursor..].chars().next() {
+                    Some('[') => break,
+                    Some(c) if c.is_ascii() => cursor += 1,
+                    _ => return (tokens, Some(start)),
+                }
+            }
+            skip_whitespace(&mut cursor);
+            while text[cursor..].starts_with('[') {
+                let mut escaped = false;
+                let mut chars = text[cursor + 1..].char_indices();
+                let end = loop {
+                    match chars.next() {
+                        Some((i, ']')) if !escaped => break cursor + 1 + i + 1,
+                        Some((_, '\\')) if !escaped => escaped = true,
+                        Some(_) => escaped = false,
+                        None => return (tokens, Some(start)),
+                    }
+                };
+                cursor = end;
+                skip_whitespace(&mut cursor);
+            }
+        }
+        tokens.push((kind, start..cursor));
+        skip_whitespace(&mut cursor);
+    }
+    (tokens, None)
+}
@@ -0,0 +1,35 @@
+use std::io::{BufRead, BufReader, Read};
+
+use flate2::bufread::MultiGzDecoder;
+use minidom::Element;
+
+use crate::errors::MakeSvgError;
+use crate::make_svg::{make_svg, MakeSvgOptions};
+
+/// The first two bytes of every gzip stream.
+static GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read SGF text from `reader`, decompressing it first if it's gzipped (e.g. a `.sgf.gz` file).
+pub fn read_sgf<R: Read>(reader: R) -> Result<String, MakeSvgError> {
+    let mut reader = BufReader::new(reader);
+    let is_gzipped = reader
+        .fill_buf()
+        .map_err(MakeSvgError::ReadError)?
+        .starts_with(&GZIP_MAGIC);
+    let mut bytes = vec![];
+    if is_gzipped {
+        MultiGzDecoder::new(reader).read_to_end(&mut bytes)
+    } else {
+        reader.read_to_end(&mut bytes)
+    }
+    .map_err(MakeSvgError::ReadError)?;
+    String::from_utf8(bytes).map_err(MakeSvgError::InvalidUtf8)
+}
+
+/// Render plain or gzipped SGF read from `reader`. See `make_svg`.
+pub fn make_svg_from_reader<R: Read>(
+    reader: R,
+    options: &MakeSvgOptions,
+) -> Result<Element, MakeSvgError> {
+    make_svg(&read_sgf(reader)?, options)
+}
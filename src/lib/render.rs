@@ -0,0 +1,69 @@
+use crate::args::OutputFormat;
+use crate::errors::MakeSvgError;
+use crate::make_ascii::make_ascii;
+use crate::make_svg::{make_svg_to_writer, MakeSvgOptions};
+
+/// Settings which only apply to some output formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatSettings {
+    /// Resolution of PNG and WebP output (96 gives one pixel per unit of width).
+    pub dpi: f64,
+    /// Delay between GIF frames in milliseconds.
+    pub frame_delay_ms: u16,
+    /// Quality of lossy WebP output, from 0 to 100.
+    pub webp_quality: f32,
+}
+
+impl Default for FormatSettings {
+    fn default() -> Self {
+        Self {
+            dpi: 96.0,
+            frame_delay_ms: 500,
+            webp_quality: 80.0,
+        }
+    }
+}
+
+/// Render `sgf` in the given format, with the default `FormatSettings`.
+///
+/// Text formats (SVG, ASCII and JSON) are returned as UTF-8.
+pub fn render(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    format: OutputFormat,
+) -> Result<Vec<u8>, MakeSvgError> {
+    render_with_settings(sgf, options, format, &FormatSettings::default())
+}
+
+/// Render `sgf` in the given format. See `render`.
+#[cfg_attr(not(feature = "png"), allow(unused_variables))]
+pub fn render_with_settings(
+    sgf: &str,
+    options: &MakeSvgOptions,
+    format: OutputFormat,
+    settings: &FormatSettings,
+) -> Result<Vec<u8>, MakeSvgError> {
+    options.validate()?;
+    match format {
+        OutputFormat::Svg => {
+            let mut buffer = vec![];
+            make_svg_to_writer(sgf, options, &mut buffer)?;
+            Ok(buffer)
+        }
+        OutputFormat::Ascii => Ok(make_ascii(sgf, options)?.into_bytes()),
+        #[cfg(feature = "png")]
+        OutputFormat::Png => crate::make_png::make_png(sgf, options, settings.dpi),
+        #[cfg(feature = "gif")]
+        OutputFormat::Gif => {
+            crate::make_animation::make_animation(sgf, options, settings.frame_delay_ms)
+        }
+        #[cfg(feature = "pdf")]
+        OutputFormat::Pdf => crate::make_pdf::make_pdf(sgf, options),
+        #[cfg(feature = "webp")]
+        OutputFormat::Webp => {
+            crate::make_webp::make_webp(sgf, options, settings.dpi, settings.webp_quality)
+        }
+        #[cfg(feature = "json")]
+        OutputFormat::Json => Ok(crate::make_json::make_json(sgf, options)?.into_bytes()),
+    }
+}
@@ -2,85 +2,97 @@ use std::error::Error;
 use std::path::Path;
 
 use clap::Parser;
-use minidom::Element;
 
-use sgf_render::{OutputFormat, SgfRenderArgs};
+use sgf_render::{FormatSettings, InputFormat, SgfRenderArgs};
 
 fn main() {
     let parsed_args = SgfRenderArgs::parse();
     let options = match parsed_args.make_svg_args.options() {
         Ok(options) => options,
         Err(e) => {
-            eprintln!("Failed to parse arguments: {}", e);
+            eprintln!("Failed to parse arguments: {}", describe(&e));
             std::process::exit(1);
         }
     };
 
-    let input = match read_input(parsed_args.infile) {
+    let input = match read_input(&parsed_args.infile, input_format(&parsed_args)) {
         Ok(goban) => goban,
         Err(e) => {
-            eprintln!("Failed to read input: {}", e);
+            eprintln!("Failed to read input: {}", describe(e.as_ref()));
             std::process::exit(1);
         }
     };
 
-    let svg = match sgf_render::make_svg(&input, &options) {
-        Ok(svg) => svg,
+    let settings = FormatSettings {
+        dpi: parsed_args.dpi,
+        frame_delay_ms: parsed_args.frame_delay,
+        webp_quality: parsed_args.webp_quality,
+    };
+    let output = match sgf_render::render_with_settings(
+        &input,
+        &options,
+        parsed_args.output_format,
+        &settings,
+    ) {
+        Ok(output) => output,
         Err(e) => {
-            eprintln!("Failed to generate SVG: {}", e);
+            eprintln!("Failed to generate diagram: {}", describe(&e));
             std::process::exit(1);
         }
     };
 
-    if let Err(e) = write_output(&svg, parsed_args.outfile, parsed_args.output_format) {
-        eprintln!("Failed to write output: {}", e);
+    if let Err(e) = write_output(&output, &parsed_args.outfile) {
+        eprintln!("Failed to write output: {}", describe(e.as_ref()));
         std::process::exit(1);
     }
 }
 
-fn read_input<P: AsRef<Path>>(infile: Option<P>) -> Result<String, Box<dyn Error>> {
-    let mut reader: Box<dyn std::io::Read> = match infile {
-        Some(filename) => Box::new(std::io::BufReader::new(std::fs::File::open(&filename)?)),
+fn input_format(args: &SgfRenderArgs) -> InputFormat {
+    args.input_format.unwrap_or_else(|| match &args.infile {
+        Some(path) if path.extension().is_some_and(|ext| ext == "gib") => InputFormat::Gib,
+        _ => InputFormat::Sgf,
+    })
+}
+
+fn read_input<P: AsRef<Path>>(
+    infile: &Option<P>,
+    input_format: InputFormat,
+) -> Result<String, Box<dyn Error>> {
+    let reader: Box<dyn std::io::Read> = match infile {
+        Some(filename) => Box::new(std::io::BufReader::new(std::fs::File::open(filename)?)),
         None => Box::new(std::io::stdin()),
     };
-    let mut input = String::new();
-    reader.read_to_string(&mut input)?;
-    Ok(input)
+    #[cfg(feature = "gzip")]
+    let input = sgf_render::read_sgf(reader)?;
+    #[cfg(not(feature = "gzip"))]
+    let input = {
+        let mut reader = reader;
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        input
+    };
+    match input_format {
+        InputFormat::Sgf => Ok(input),
+        InputFormat::Gib => Ok(sgf_render::gib_to_sgf(&input)?),
+    }
 }
 
-fn write_output<P: AsRef<Path>>(
-    svg: &Element,
-    outfile: Option<P>,
-    format: OutputFormat,
-) -> Result<(), Box<dyn Error>> {
+fn write_output<P: AsRef<Path>>(output: &[u8], outfile: &Option<P>) -> Result<(), Box<dyn Error>> {
     let mut writer: Box<dyn std::io::Write> = match outfile {
         Some(path) => Box::new(std::fs::File::create(path)?),
         None => Box::new(std::io::stdout()),
     };
-    match format {
-        OutputFormat::Svg => svg.write_to(&mut writer)?,
-        #[cfg(feature = "png")]
-        OutputFormat::Png => save_png(writer, svg)?,
-    }
+    writer.write_all(output)?;
     Ok(())
 }
 
-#[cfg(feature = "png")]
-fn save_png(mut writer: Box<dyn std::io::Write>, svg: &Element) -> Result<(), Box<dyn Error>> {
-    let tree = {
-        let mut buffer: Vec<u8> = vec![];
-        svg.write_to(&mut buffer)?;
-        let mut fontdb = usvg::fontdb::Database::new();
-        fontdb.load_font_data(include_bytes!("../resources/Inter-Bold.ttf").to_vec());
-        usvg::Tree::from_data(&buffer, &usvg::Options::default(), &fontdb)?
-    };
-    let data = {
-        let pixmap_size = tree.size().to_int_size();
-        let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height()).unwrap();
-        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-        pixmap.encode_png()?
-    };
-
-    writer.write_all(&data)?;
-    Ok(())
+/// The error followed by each of its underlying causes.
+fn describe(error: &dyn Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        description.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    description
 }
@@ -0,0 +1,1876 @@
+use clap::Parser;
+
+use sgf_render::{
+    game_info, game_length, game_positions, gib_to_sgf, handicap_points, make_ascii,
+    make_comparison_svg, make_figures, make_layered_svg, make_svg, make_svg_from_collection,
+    make_svg_from_reader, make_svg_sequence, make_svg_to_writer, make_tree_svg,
+    render_changed_region, render_figures_to_files, render_with_move_table, Goban, MakeSvgArgs,
+    MakeSvgError, MarkupSet, NodeDescription, OutputFormat, ResultFormat, SizeSpec, StoneColor,
+};
+
+fn options_from(args: &[&str]) -> sgf_render::MakeSvgOptions {
+    let mut arguments = vec!["sgf-render"];
+    arguments.extend_from_slice(args);
+    MakeSvgArgs::parse_from(arguments).options().unwrap()
+}
+
+#[test]
+fn changed_region_single_move() {
+    let sgf = "(;SZ[9];B[cc];W[gg];B[dd])";
+    let options = options_from(&[]);
+    let from: NodeDescription = "2".parse().unwrap();
+    let to: NodeDescription = "3".parse().unwrap();
+    let (_, (x_range, y_range)) = render_changed_region(sgf, &options, &from, &to).unwrap();
+    assert_eq!((x_range, y_range), (3..4, 3..4));
+}
+
+#[test]
+fn changed_region_includes_captures() {
+    let sgf = "(;SZ[9];B[ba];W[aa];B[ab])";
+    let options = options_from(&[]);
+    let from: NodeDescription = "2".parse().unwrap();
+    let to: NodeDescription = "3".parse().unwrap();
+    let (_, (x_range, y_range)) = render_changed_region(sgf, &options, &from, &to).unwrap();
+    assert_eq!((x_range, y_range), (0..1, 0..2));
+}
+
+#[test]
+fn changed_region_unchanged_is_full_board() {
+    let sgf = "(;SZ[9];B[cc])";
+    let options = options_from(&[]);
+    let node: NodeDescription = "1".parse().unwrap();
+    let (_, (x_range, y_range)) = render_changed_region(sgf, &options, &node, &node).unwrap();
+    assert_eq!((x_range, y_range), (0..9, 0..9));
+}
+
+#[test]
+fn move_table() {
+    let sgf = "(;SZ[19];B[pd];W[dp];B[pp])";
+    let options = options_from(&["-n", "last", "--move-numbers=2"]);
+    let (_, table) = render_with_move_table(sgf, &options).unwrap();
+    assert_eq!(table, vec![(1, "D4".to_string()), (2, "Q4".to_string())]);
+}
+
+#[test]
+fn move_table_without_move_numbers() {
+    let sgf = "(;SZ[19];B[pd];W[dp])";
+    let options = options_from(&["-n", "last"]);
+    let (_, table) = render_with_move_table(sgf, &options).unwrap();
+    assert!(table.is_empty());
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png_dimensions_scale_with_dpi() {
+    let sgf = "(;SZ[9];B[cc])";
+    let options = options_from(&["--width", "100"]);
+    let png = sgf_render::make_png(sgf, &options, 192.0).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    assert_eq!(width, 200);
+}
+
+#[cfg(feature = "webp")]
+#[test]
+fn webp_dimensions_scale_with_dpi() {
+    let sgf = "(;SZ[9];B[cc])";
+    let options = options_from(&["--width", "100"]);
+    let webp = sgf_render::make_webp(sgf, &options, 192.0, 80.0).unwrap();
+    assert_eq!(&webp[..4], b"RIFF");
+    assert_eq!(&webp[8..16], b"WEBPVP8 ");
+    // The lossy frame header stores the width in 14 bits after the start code.
+    let width = (u32::from(webp[26]) | u32::from(webp[27]) << 8) & 0x3fff;
+    assert_eq!(width, 200);
+}
+
+#[cfg(feature = "webp")]
+#[test]
+fn webp_quality_trades_size() {
+    let sgf = "(;SZ[19]KM[6.5];B[pd];W[dp];B[pq];W[dd];B[fq];W[cn];B[jp])";
+    let options = options_from(&["--style", "fancy", "--width", "400"]);
+    let low = sgf_render::make_webp(sgf, &options, 96.0, 10.0).unwrap();
+    let high = sgf_render::make_webp(sgf, &options, 96.0, 95.0).unwrap();
+    assert!(low.len() < high.len());
+    assert!(matches!(
+        sgf_render::make_webp(sgf, &options, 96.0, 101.0),
+        Err(MakeSvgError::WebpError(_))
+    ));
+}
+
+#[cfg(feature = "batch")]
+#[test]
+fn batch_preserves_order_and_isolates_errors() {
+    let inputs = vec![
+        ("(;SZ[9];B[cc])", options_from(&["-n", "last"])),
+        ("(;SZ[9];B[cc]", options_from(&[])),
+        (
+            "(;SZ[13];W[dd])",
+            options_from(&["-n", "last", "--move-numbers"]),
+        ),
+    ];
+    let results = sgf_render::make_svg_batch(&inputs);
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].as_ref().unwrap(),
+        &make_svg(inputs[0].0, &inputs[0].1).unwrap()
+    );
+    assert!(matches!(results[1], Err(MakeSvgError::ParseError(..))));
+    assert_eq!(
+        results[2].as_ref().unwrap(),
+        &make_svg(inputs[2].0, &inputs[2].1).unwrap()
+    );
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn pdf_output() {
+    let sgf = "(;SZ[9];B[cc]C[Black plays])";
+    let options = options_from(&["--show-comment"]);
+    let pdf = sgf_render::make_pdf(sgf, &options).unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.ends_with(b"%%EOF") || pdf.ends_with(b"%%EOF\n"));
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn crisp_edges_changes_png() {
+    let sgf = "(;SZ[9];B[cc];W[gg])";
+    let smooth = sgf_render::make_png(sgf, &options_from(&["--width", "100"]), 96.0).unwrap();
+    let crisp = sgf_render::make_png(
+        sgf,
+        &options_from(&["--width", "100", "--shape-rendering", "crisp-edges"]),
+        96.0,
+    )
+    .unwrap();
+    assert_ne!(smooth, crisp);
+}
+
+#[cfg(feature = "gif")]
+#[test]
+fn animation_has_frame_per_node() {
+    let sgf = "(;SZ[9];B[cc];W[gg];B[dd])";
+    let options = options_from(&["--width", "90"]);
+    let gif = sgf_render::make_animation(sgf, &options, 200).unwrap();
+    assert_eq!(&gif[..6], b"GIF89a");
+    // Each frame starts with a graphic control extension block.
+    let frames = gif.windows(3).filter(|w| w == b"\x21\xf9\x04").count();
+    assert_eq!(frames, 4);
+}
+
+#[test]
+fn svg_to_writer_matches_make_svg() {
+    let sgf = "(;SZ[9];B[cc];W[gg])";
+    let options = options_from(&["-n", "last"]);
+    let mut expected = vec![];
+    make_svg(sgf, &options)
+        .unwrap()
+        .write_to(&mut expected)
+        .unwrap();
+    let mut written = vec![];
+    make_svg_to_writer(sgf, &options, &mut written).unwrap();
+    assert_eq!(written, expected);
+}
+
+#[test]
+fn repeated_renders_are_identical() {
+    // Stones and markup are stored in hash maps, so each render iterates them in a different
+    // order unless they're sorted before drawing.
+    let sgf = "(;SZ[19]AB[aa][pd][dp][jj][cq][qc]AW[pp][dd][jk][kj]TR[aa][pp]SQ[jj]\
+               LB[dd:A][pd:B]TB[ss][rs]TW[ab][ac];B[qq];W[qo];B[op])";
+    let options = options_from(&["-n", "last", "--move-numbers", "--territory"]);
+    let render = || {
+        let mut buffer = vec![];
+        make_svg_to_writer(sgf, &options, &mut buffer).unwrap();
+        buffer
+    };
+    let first = render();
+    for _ in 0..10 {
+        assert_eq!(render(), first);
+    }
+}
+
+#[test]
+fn svg_sequence_matches_individual_renders() {
+    let sgf = "(;SZ[9];B[cc];W[gg];B[dd])";
+    let options = options_from(&[]);
+    let svgs = make_svg_sequence(sgf, &options, &[0, 2, 3]).unwrap();
+    assert_eq!(svgs.len(), 3);
+    for (svg, node) in svgs.iter().zip(&["0", "2", "3"]) {
+        let expected = make_svg(sgf, &options_from(&["-n", node])).unwrap();
+        assert_eq!(svg, &expected);
+    }
+}
+
+#[test]
+fn figures_split_at_fg() {
+    let sgf = "(;SZ[9];B[cc];W[gg]FG[];B[dd];W[ee];B[ff]FG[];W[hh])";
+    let options = options_from(&[]);
+    let figures = make_figures(sgf, &options).unwrap();
+    assert_eq!(figures.len(), 3);
+    let expected_renders = [
+        [
+            "-n",
+            "1",
+            "--move-numbers=1-1",
+            "--move-number-display",
+            "absolute",
+        ],
+        [
+            "-n",
+            "4",
+            "--move-numbers=2-4",
+            "--move-number-display",
+            "absolute",
+        ],
+        [
+            "-n",
+            "6",
+            "--move-numbers=5-6",
+            "--move-number-display",
+            "absolute",
+        ],
+    ];
+    for (figure, args) in figures.iter().zip(&expected_renders) {
+        let expected = make_svg(sgf, &options_from(args)).unwrap();
+        assert_eq!(figure, &expected);
+    }
+}
+
+#[test]
+fn ascii_board() {
+    let sgf = "(;SZ[9];B[cc];W[gc];B[ee])";
+    let options = options_from(&["-n", "last", "--range", "aa-ig"]);
+    let ascii = make_ascii(sgf, &options).unwrap();
+    let expected = "\
+. . . . . . . . .
+. . . . . . . . .
+. . X . . . O . .
+. . . . . . . . .
+. . . . X . . . .
+. . . . . . . . .
+. . + . . . + . .
+";
+    assert_eq!(ascii, expected);
+}
+
+#[test]
+fn gib_conversion() {
+    let gib = "\\HS
+\\[GAMEBLACKNAME=Black (4d)\\]
+\\[GAMEWHITENAME=White (5d)\\]
+\\HE
+\\GS
+2 1 0
+127 0 &4
+INI 0 1 2 &4
+STO 0 2 2 15 15
+SKI 0 3
+STO 0 4 2 2 16
+\\GE
+";
+    let sgf = gib_to_sgf(gib).unwrap();
+    assert_eq!(
+        sgf,
+        "(;GM[1]FF[4]SZ[19]PB[Black (4d)]PW[White (5d)]HA[2]AB[pd][dp]PL[W];W[pp];B[];W[cq])"
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_description() {
+    let sgf = "(;SZ[9];B[cc];W[gg]TR[cc])";
+    let options = options_from(&["-n", "last", "--range", "bb-hh"]);
+    let json = sgf_render::make_json(sgf, &options).unwrap();
+    assert!(json.starts_with(r#"{"size":[9,9],"x_range":{"start":1,"end":8}"#));
+    assert!(
+        json.contains(r#""stones":[{"x":2,"y":2,"color":"black"},{"x":6,"y":6,"color":"white"}]"#)
+    );
+    assert!(json.contains(r#""last_move":[6,6]"#));
+    assert!(json.contains(r#""triangles":[[2,2]]"#));
+}
+
+#[test]
+fn missing_game_index() {
+    let sgf = "(;SZ[9];B[cc])(;SZ[9];W[cc])";
+    let options = options_from(&["--game", "2"]);
+    let error = make_svg(sgf, &options).unwrap_err();
+    assert_eq!(error.to_string(), "Selected game not found in collection.");
+}
+
+fn find_by_id<'a>(element: &'a minidom::Element, id: &str) -> Option<&'a minidom::Element> {
+    if element.attr("id") == Some(id) {
+        return Some(element);
+    }
+    element.children().find_map(|child| find_by_id(child, id))
+}
+
+/// The settings a style needs besides its stone markup colors, for tests building a style
+/// around them.
+const BASE_STYLE: &str = "line_color = \"black\"\n\
+                          background_fill = \"white\"\n\
+                          label_color = \"black\"\n\
+                          empty_markup_color = \"black\"\n\
+                          black_stone_selected_color = \"blue\"\n\
+                          white_stone_selected_color = \"blue\"\n\
+                          empty_selected_color = \"blue\"\n";
+
+/// The `text` element directly inside `element`, such as a label's or move number's group.
+fn text_child(element: &minidom::Element) -> &minidom::Element {
+    element
+        .children()
+        .find(|child| child.name() == "text")
+        .unwrap()
+}
+
+fn parse_pair(transform: &str, name: &str) -> (f64, f64) {
+    let inner = transform
+        .strip_prefix(name)
+        .and_then(|s| s.strip_prefix('('))
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap();
+    let (a, b) = inner.split_once(',').unwrap();
+    (a.trim().parse().unwrap(), b.trim().parse().unwrap())
+}
+
+#[test]
+fn corner_crop_labels_are_not_cut_off() {
+    let sgf = "(;SZ[19];B[aa];W[bb])";
+    let options = options_from(&["--range", "aa-cc", "--label-sides", "nesw"]);
+    let svg = make_svg(sgf, &options).unwrap();
+    let viewbox: Vec<f64> = svg
+        .attr("viewBox")
+        .unwrap()
+        .split(' ')
+        .map(|s| s.parse().unwrap())
+        .collect();
+    let (width, height) = (viewbox[2], viewbox[3]);
+    let diagram = find_by_id(&svg, "diagram").unwrap();
+    let (scale, _) = parse_pair(diagram.attr("transform").unwrap(), "scale");
+    let labels = find_by_id(diagram, "board-labels").unwrap();
+    let (dx, dy) = parse_pair(labels.attr("transform").unwrap(), "translate");
+    let font_size = 0.45 * scale;
+    let mut count = 0;
+    for side in labels.children() {
+        for text in side.children() {
+            let x = (dx + text.attr("x").unwrap().parse::<f64>().unwrap()) * scale;
+            let y = (dy + text.attr("y").unwrap().parse::<f64>().unwrap()) * scale;
+            assert!(x >= font_size / 2.0 && x <= width - font_size / 2.0);
+            assert!(y >= font_size / 2.0 && y <= height - font_size / 2.0);
+            count += 1;
+        }
+    }
+    assert_eq!(count, 12);
+}
+
+#[test]
+fn game_info_from_root() {
+    let sgf = "(;SZ[13]PB[Black]BR[3k]PW[White]KM[0.5]HA[2]RE[B+R]DT[2024-01-02]EV[Club];B[cc])";
+    let info = game_info(sgf).unwrap();
+    assert_eq!(info.black_player.as_deref(), Some("Black"));
+    assert_eq!(info.black_rank.as_deref(), Some("3k"));
+    assert_eq!(info.white_player.as_deref(), Some("White"));
+    assert_eq!(info.white_rank, None);
+    assert_eq!(info.komi, Some(0.5));
+    assert_eq!(info.handicap, Some(2));
+    assert_eq!(info.result.as_deref(), Some("B+R"));
+    assert_eq!(info.date.as_deref(), Some("2024-01-02"));
+    assert_eq!(info.event.as_deref(), Some("Club"));
+    assert_eq!(info.board_size, (13, 13));
+}
+
+#[test]
+fn arrowheads_scale_with_line_width() {
+    let sgf = "(;SZ[9]AR[cc:gg]LN[cg:gc])";
+    let arrowhead_size = |line_width: f64| {
+        let mut options = options_from(&[]);
+        options.style = toml::from_str(&format!(
+            "{}line_width = {}\n\
+             black_stone_markup_color = \"white\"\n\
+             white_stone_markup_color = \"black\"\n",
+            BASE_STYLE, line_width
+        ))
+        .unwrap();
+        let svg = make_svg(sgf, &options).unwrap();
+        let marker = find_by_id(&svg, "arrowhead").unwrap();
+        assert_eq!(
+            marker.attr("markerUnits").unwrap_or("strokeWidth"),
+            "strokeWidth"
+        );
+        let marker_width: f64 = marker.attr("markerWidth").unwrap().parse().unwrap();
+        let arrows = find_by_id(&svg, "markup-arrows").unwrap();
+        let stroke_width: f64 = arrows.attr("stroke-width").unwrap().parse().unwrap();
+        marker_width * stroke_width
+    };
+    let thin = arrowhead_size(0.03);
+    let thick = arrowhead_size(0.06);
+    assert!((thick / thin - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn line_ends_match_the_markup_color() {
+    let sgf = "(;SZ[9]AR[cc:gg]LN[cg:gc])";
+    let svg = make_svg(
+        sgf,
+        &options_from(&["--style", "dark", "--arrowhead-style", "open"]),
+    )
+    .unwrap();
+    let arrows = find_by_id(&svg, "markup-arrows").unwrap();
+    assert_eq!(arrows.attr("stroke"), Some("#e0e0e0"));
+    let arrowhead = find_by_id(&svg, "arrowhead").unwrap();
+    let open_head = arrowhead.children().next().unwrap();
+    assert_eq!(open_head.attr("stroke"), Some("#e0e0e0"));
+    let linehead = find_by_id(&svg, "linehead").unwrap();
+    let filled_head = linehead.children().next().unwrap();
+    assert_eq!(filled_head.attr("fill"), Some("#e0e0e0"));
+}
+
+#[test]
+fn goban_from_sgf() {
+    let sgf = "(;SZ[9];B[cc];W[gg];B[dd])";
+    let node: NodeDescription = "2".parse().unwrap();
+    let goban = Goban::from_sgf(sgf, &node).unwrap();
+    assert_eq!(goban.size(), (9, 9));
+    assert_eq!(goban.stone_color(2, 2), Some(StoneColor::Black));
+    assert_eq!(goban.stone_color(6, 6), Some(StoneColor::White));
+    assert_eq!(goban.stone_color(3, 3), None);
+    assert_eq!(goban.last_move(), Some((6, 6)));
+}
+
+#[test]
+fn tree_svg_layout() {
+    let sgf = "(;SZ[9];B[cc](;W[dd];B[ee](;W[ff])(;W[gg]))(;W[ff]))";
+    let svg = make_tree_svg(sgf, &options_from(&[])).unwrap();
+    let nodes = find_by_id(&svg, "nodes").unwrap();
+    let positions: Vec<(&str, &str, &str)> = nodes
+        .children()
+        .map(|c| {
+            (
+                c.attr("cx").unwrap(),
+                c.attr("cy").unwrap(),
+                c.attr("fill").unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        positions,
+        vec![
+            ("0.5", "0.5", "black"),
+            ("1.5", "0.5", "black"),
+            ("2.5", "0.5", "white"),
+            ("3.5", "0.5", "black"),
+            ("4.5", "0.5", "white"),
+            ("4.5", "1.5", "white"),
+            ("2.5", "2.5", "white"),
+        ]
+    );
+    assert_eq!(find_by_id(&svg, "edges").unwrap().children().count(), 6);
+}
+
+#[test]
+fn add_empty_removes_setup_stones() {
+    let sgf = "(;SZ[9]AB[aa][bb]AW[cc];AE[bb])";
+    let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+    let mut stones: Vec<_> = goban.stones().map(|s| (s.x, s.y, s.color)).collect();
+    stones.sort_by_key(|&(x, y, _)| (x, y));
+    assert_eq!(
+        stones,
+        vec![(0, 0, StoneColor::Black), (2, 2, StoneColor::White)]
+    );
+}
+
+#[test]
+fn corner_triangle_is_inside_clip() {
+    let sgf = "(;SZ[19]TR[aa][cc])";
+    let triangle_extent = |svg: &minidom::Element| {
+        let triangles = find_by_id(svg, "markup-triangles").unwrap();
+        let mut extent = (f64::MAX, f64::MAX);
+        for polygon in triangles.children().flat_map(|g| g.children()) {
+            for point in polygon.attr("points").unwrap().split_whitespace() {
+                let (x, y) = point.split_once(',').unwrap();
+                extent.0 = extent.0.min(x.parse().unwrap());
+                extent.1 = extent.1.min(y.parse().unwrap());
+            }
+        }
+        extent
+    };
+    let clip_origin = |svg: &minidom::Element| {
+        let rect = find_by_id(svg, "board-clip")
+            .unwrap()
+            .children()
+            .next()
+            .unwrap();
+        let x: f64 = rect.attr("x").unwrap().parse().unwrap();
+        let y: f64 = rect.attr("y").unwrap().parse().unwrap();
+        (x, y)
+    };
+
+    let svg = make_svg(sgf, &options_from(&["--range", "aa-cc"])).unwrap();
+    let (min_x, min_y) = triangle_extent(&svg);
+    let (clip_x, clip_y) = clip_origin(&svg);
+    // Leave room for the stroke.
+    assert!(min_x - 0.03 > clip_x && min_y - 0.03 > clip_y);
+
+    let svg = make_svg(
+        sgf,
+        &options_from(&["--range", "aa-cc", "--clip-margin", "0.1"]),
+    )
+    .unwrap();
+    assert_eq!(clip_origin(&svg), (-0.6, -0.6));
+}
+
+#[test]
+fn handicap_placement() {
+    assert_eq!(handicap_points((19, 19), 2), vec![(15, 3), (3, 15)]);
+    assert_eq!(
+        handicap_points((13, 13), 5),
+        vec![(3, 3), (9, 3), (6, 6), (3, 9), (9, 9)]
+    );
+    assert_eq!(handicap_points((9, 9), 9).len(), 9);
+    assert!(handicap_points((19, 19), 10).is_empty());
+    assert!(handicap_points((15, 15), 4).is_empty());
+}
+
+#[test]
+fn handicap_stones_inferred_from_ha() {
+    let goban = Goban::from_sgf("(;SZ[19]HA[3];W[qq])", &"last".parse().unwrap()).unwrap();
+    let mut black: Vec<_> = goban
+        .stones()
+        .filter(|s| s.color == StoneColor::Black)
+        .map(|s| (s.x, s.y))
+        .collect();
+    black.sort_unstable();
+    assert_eq!(black, vec![(3, 15), (15, 3), (15, 15)]);
+
+    // Explicit setup stones take precedence.
+    let goban = Goban::from_sgf("(;SZ[19]HA[3]AB[dd])", &"last".parse().unwrap()).unwrap();
+    assert_eq!(goban.stones().count(), 1);
+}
+
+#[test]
+fn transparent_background_has_no_background_rect() {
+    let sgf = "(;SZ[9];B[cc])";
+    let is_background =
+        |e: &minidom::Element| e.name() == "rect" && e.attr("width") == Some("100%");
+    let svg = make_svg(sgf, &options_from(&[])).unwrap();
+    assert!(svg.children().any(is_background));
+    let svg = make_svg(
+        sgf,
+        &options_from(&["-n", "last", "--transparent-background"]),
+    )
+    .unwrap();
+    assert!(!svg.children().any(is_background));
+    assert!(find_by_id(&svg, "lines").is_some());
+    assert_eq!(find_by_id(&svg, "stones").unwrap().children().count(), 1);
+}
+
+#[test]
+fn move_numbers_contrast_with_stones() {
+    let sgf = "(;SZ[9];B[cc];W[gg])";
+    let mut options = options_from(&["-n", "last", "--move-numbers"]);
+    // Markup colors which would be nearly invisible on the stones they're drawn on.
+    options.style = toml::from_str(&format!(
+        "{}black_stone_fill = \"#111\"\n\
+         white_stone_fill = \"#eeeeee\"\n\
+         black_stone_markup_color = \"#333\"\n\
+         white_stone_markup_color = \"#ddd\"\n",
+        BASE_STYLE
+    ))
+    .unwrap();
+    let svg = make_svg(sgf, &options).unwrap();
+    let fills: Vec<(&str, String)> = find_by_id(&svg, "move-numbers")
+        .unwrap()
+        .children()
+        .flat_map(|group| group.children())
+        .map(|text| (text.attr("fill").unwrap(), text.text()))
+        .collect();
+    assert_eq!(
+        fills,
+        vec![("white", "1".to_string()), ("black", "2".to_string())]
+    );
+}
+
+#[test]
+fn numbered_stones_only_hides_markup_on_stones() {
+    let sgf = "(;SZ[9];B[cc];W[gg]CR[cc][ee])";
+    let options = options_from(&["-n", "last", "--last-move", "--numbered-stones-only"]);
+    let svg = make_svg(sgf, &options).unwrap();
+    // Only the circle on the empty point is drawn.
+    assert_eq!(
+        find_by_id(&svg, "markup-circles")
+            .unwrap()
+            .children()
+            .count(),
+        1
+    );
+    assert_eq!(find_by_id(&svg, "last-move").unwrap().children().count(), 0);
+}
+
+#[test]
+fn column_labels_skip_i_by_default() {
+    let column_labels = |args: &[&str]| -> String {
+        let svg = make_svg("(;SZ[19])", &options_from(args)).unwrap();
+        find_by_id(&svg, "board-labels")
+            .unwrap()
+            .children()
+            .next()
+            .unwrap()
+            .children()
+            .map(|text| text.text())
+            .collect()
+    };
+    assert_eq!(column_labels(&[]), "ABCDEFGHJKLMNOPQRST");
+    assert_eq!(
+        column_labels(&["--include-i-column"]),
+        "ABCDEFGHIJKLMNOPQRS"
+    );
+}
+
+#[test]
+fn parse_errors_report_location() {
+    let location = |sgf: &str| match make_svg(sgf, &options_from(&[])) {
+        Err(MakeSvgError::ParseError(_, Some(location))) => location,
+        result => panic!("Expected parse error, got {:?}", result),
+    };
+    // A property outside of any node.
+    let sgf = "(;SZ[9]\n;B[cc]\n(W[dd]))";
+    let error_location = location(sgf);
+    assert_eq!(error_location.offset, 16);
+    assert_eq!((error_location.line, error_location.column), (3, 2));
+    assert!(error_location.snippet.contains("W[dd]"));
+    // An unterminated property value.
+    let error_location = location("(;SZ[9];B[cc];W[dd)");
+    assert_eq!(error_location.offset, 14);
+    // An extra closing paren.
+    let error_location = location("(;SZ[9];B[cc]))(;SZ[9])");
+    assert_eq!(error_location.offset, 14);
+    let message = make_svg("(;SZ[9];B[cc]))", &options_from(&[]))
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("line 1, column 15"), "{}", message);
+}
+
+#[test]
+fn goban_occupancy_queries() {
+    let goban = Goban::from_sgf("(;SZ[9];B[cc];W[ia])", &"last".parse().unwrap()).unwrap();
+    assert_eq!(goban.stone_at((2, 2)), Some(StoneColor::Black));
+    assert_eq!(goban.stone_at((8, 0)), Some(StoneColor::White));
+    assert_eq!(goban.stone_at((3, 3)), None);
+    assert_eq!(goban.stone_at((9, 0)), None);
+    assert!(goban.is_on_board((8, 8)));
+    assert!(!goban.is_on_board((9, 8)));
+    assert!(!goban.is_on_board((8, 9)));
+}
+
+#[test]
+fn numbering_resets_per_segment() {
+    let sgf = "(;SZ[9];B[cc];W[gg];B[dd]FG[];W[ee];B[ff];W[hh]FG[];B[bb])";
+    let mut options = options_from(&["--move-numbers"]);
+    options.reset_numbering_per_segment = true;
+    let numbers = |svg: &minidom::Element| -> Vec<String> {
+        find_by_id(svg, "move-numbers")
+            .unwrap()
+            .children()
+            .flat_map(|group| group.children())
+            .map(|text| text.text())
+            .collect()
+    };
+    let sequence = make_svg_sequence(sgf, &options, &[2, 5, 7]).unwrap();
+    let sequence_numbers: Vec<_> = sequence.iter().map(numbers).collect();
+    assert_eq!(
+        sequence_numbers,
+        vec![vec!["1", "2"], vec!["1", "2", "3"], vec!["1", "2"]]
+    );
+    let figures = make_figures(sgf, &options).unwrap();
+    let figure_numbers: Vec<_> = figures.iter().map(numbers).collect();
+    assert_eq!(
+        figure_numbers,
+        vec![vec!["1", "2"], vec!["1", "2", "3"], vec!["1", "2"]]
+    );
+}
+
+#[test]
+fn view_range_falls_back_to_full_board() {
+    let sgf = "(;SZ[9]VW[aa:cc];B[ee];W[ff]VW[])";
+    let ascii =
+        |node: &str| make_ascii(sgf, &options_from(&["-n", node, "--range", "view"])).unwrap();
+    assert_eq!(ascii("1").lines().count(), 3);
+    // An empty VW[] makes the whole board visible again.
+    assert_eq!(ascii("2").lines().count(), 9);
+    let no_view = make_ascii("(;SZ[9])", &options_from(&["--range", "view"])).unwrap();
+    assert_eq!(no_view.lines().count(), 9);
+}
+
+#[test]
+fn move_comments_collected_up_to_node() {
+    let sgf = "(;SZ[9]C[root];B[cc]C[first];W[gg]C[ ];B[dd];W[ee]C[fourth];B[ff]C[fifth])";
+    let goban = Goban::from_sgf(sgf, &"4".parse().unwrap()).unwrap();
+    assert_eq!(
+        goban.move_comments(),
+        [(1, "first".to_string()), (4, "fourth".to_string())]
+    );
+}
+
+#[test]
+fn invalid_options_rejected_before_rendering() {
+    let sgf = "(;SZ[9];B[cc])";
+    let mut options = options_from(&[]);
+    options.size = SizeSpec::TotalWidth(0.0);
+    match make_svg(sgf, &options) {
+        Err(MakeSvgError::InvalidOptions(reason)) => assert!(reason.contains("viewbox width")),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let mut options = options_from(&["--move-numbers"]);
+    if let Some(move_number_options) = options.move_number_options.as_mut() {
+        move_number_options.modulo = Some(0);
+    }
+    assert!(matches!(
+        make_svg(sgf, &options),
+        Err(MakeSvgError::InvalidOptions(_))
+    ));
+
+    let mut options = options_from(&[]);
+    options.board_margin = f64::NAN;
+    assert!(matches!(
+        options.validate(),
+        Err(MakeSvgError::InvalidOptions(_))
+    ));
+    options.board_margin = 0.0;
+    options.clip_margin = f64::NAN;
+    assert!(options.validate().is_err());
+
+    // Every entry point checks the options before rendering.
+    let mut options = options_from(&[]);
+    options.size = SizeSpec::TotalWidth(0.0);
+    let is_invalid =
+        |result: Result<(), MakeSvgError>| matches!(result, Err(MakeSvgError::InvalidOptions(_)));
+    assert!(is_invalid(make_svg_sequence(sgf, &options, &[1]).map(drop)));
+    assert!(is_invalid(make_figures(sgf, &options).map(drop)));
+    assert!(is_invalid(
+        render_changed_region(sgf, &options, &"0".parse().unwrap(), &"1".parse().unwrap())
+            .map(drop)
+    ));
+    assert!(is_invalid(render_with_move_table(sgf, &options).map(drop)));
+    assert!(is_invalid(make_ascii(sgf, &options).map(drop)));
+    assert!(is_invalid(make_tree_svg(sgf, &options).map(drop)));
+    for format in [OutputFormat::Svg, OutputFormat::Ascii] {
+        assert!(is_invalid(
+            sgf_render::render(sgf, &options, format).map(drop)
+        ));
+    }
+
+    assert!(options_from(&[]).validate().is_ok());
+}
+
+#[test]
+fn comparison_places_boards_side_by_side() {
+    let sgf = "(;SZ[9]AB[dc][ed][dd]AW[cd];B[cc];W[bd]C[atari];B[ce])";
+    let options = options_from(&["--width", "200"]);
+    let svg =
+        make_comparison_svg(sgf, "1".parse().unwrap(), "last".parse().unwrap(), &options).unwrap();
+    assert_eq!(svg.attr("viewBox"), Some("0 0 410 200"));
+    let panels: Vec<_> = svg
+        .children()
+        .filter(|child| child.name() == "svg")
+        .collect();
+    assert_eq!(panels.len(), 2);
+    assert_eq!(panels[1].attr("x"), Some("210"));
+    assert!(find_by_id(panels[0], "left-board-clip").is_some());
+    assert!(find_by_id(panels[1], "right-board-clip").is_some());
+    assert!(find_by_id(panels[1], "board-clip").is_none());
+}
+
+#[test]
+fn influence_shades_points_beneath_stones() {
+    let sgf = "(;SZ[9];B[cc])";
+    let mut options = options_from(&[]);
+    options.influence = [((2, 2), -1.0), ((3, 3), 0.0), ((4, 4), 3.0)]
+        .iter()
+        .copied()
+        .collect();
+    options.influence_colors = ("#000".to_string(), "#ffffff".to_string());
+    let svg = make_svg(sgf, &options).unwrap();
+    let goban = find_by_id(&svg, "goban").unwrap();
+    let first_group = goban.children().next().unwrap();
+    assert_eq!(first_group.attr("id"), Some("influence"));
+    let fills: Vec<_> = first_group
+        .children()
+        .map(|rect| rect.attr("fill").unwrap())
+        .collect();
+    assert_eq!(fills, ["#000000", "#808080", "#ffffff"]);
+
+    options.influence_colors.1 = "not a color".to_string();
+    assert!(matches!(
+        make_svg(sgf, &options),
+        Err(MakeSvgError::InvalidOptions(_))
+    ));
+}
+
+#[test]
+fn output_dimensions_match_rendered_viewbox() {
+    let sgf =
+        "(;SZ[13];B[cc]C[A comment long enough to wrap onto more than one line below the board.])";
+    for args in [
+        &["-n", "last", "--show-comment"][..],
+        &["--range", "aa-ff", "--width", "300"][..],
+        &["--height", "500"][..],
+    ] {
+        let options = options_from(args);
+        let (width, height) = options.output_dimensions(sgf).unwrap();
+        let svg = make_svg(sgf, &options).unwrap();
+        let viewbox: Vec<f64> = svg
+            .attr("viewBox")
+            .unwrap()
+            .split(' ')
+            .map(|value| value.parse().unwrap())
+            .collect();
+        assert!((viewbox[2] - width).abs() < 1e-3, "{:?}", args);
+        assert!((viewbox[3] - height).abs() < 1e-3, "{:?}", args);
+    }
+
+    // Both reject a stone off the board under --strict.
+    let options = options_from(&["--strict"]);
+    let off_board = "(;SZ[9]AB[kk])";
+    assert!(matches!(
+        options.output_dimensions(off_board),
+        Err(MakeSvgError::PointOffBoard(_))
+    ));
+    assert!(make_svg(off_board, &options).is_err());
+}
+
+#[test]
+fn reader_input_may_be_gzipped() {
+    use std::io::Write;
+
+    let sgf = "(;SZ[9];B[cc];W[gg])";
+    let options = options_from(&["-n", "last"]);
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    encoder.write_all(sgf.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let expected = make_svg(sgf, &options).unwrap();
+    assert_eq!(
+        make_svg_from_reader(sgf.as_bytes(), &options).unwrap(),
+        expected
+    );
+    assert_eq!(
+        make_svg_from_reader(&compressed[..], &options).unwrap(),
+        expected
+    );
+    assert!(matches!(
+        make_svg_from_reader(&b"(;SZ[9]C[\xff])"[..], &options),
+        Err(MakeSvgError::InvalidUtf8(_))
+    ));
+}
+
+#[test]
+fn move_number_background_shapes() {
+    // Black's first stone is captured, leaving its number on an empty point.
+    let sgf = "(;SZ[9];B[aa];W[ba];B[ii];W[ab])";
+    for (shape, expected) in [
+        ("rectangle", Some("rect")),
+        ("circle", Some("circle")),
+        ("none", None),
+    ] {
+        let options = options_from(&[
+            "-n",
+            "last",
+            "--move-numbers",
+            "--move-number-background",
+            shape,
+        ]);
+        let svg = make_svg(sgf, &options).unwrap();
+        let numbers = find_by_id(&svg, "move-numbers").unwrap();
+        let first = numbers.children().next().unwrap();
+        let names: Vec<_> = first.children().map(|child| child.name()).collect();
+        match expected {
+            Some(name) => assert_eq!(names, [name, "text"], "{}", shape),
+            None => assert_eq!(names, ["text"], "{}", shape),
+        }
+    }
+}
+
+#[test]
+fn quarter_turns_rotate_clockwise_after_transform() {
+    // A stone in the top right corner.
+    let sgf = "(;SZ[19];B[sa])";
+    let label = |args: &[&str], turns: u8| {
+        let mut options = options_from(args);
+        options.rotate_quarter_turns = turns;
+        let (_, table) = render_with_move_table(sgf, &options).unwrap();
+        table[0].1.clone()
+    };
+    let args = ["-n", "last", "--move-numbers"];
+    assert_eq!(label(&args, 0), "T19");
+    assert_eq!(label(&args, 1), "T1");
+    assert_eq!(label(&args, 2), "A1");
+    assert_eq!(label(&args, 3), "A19");
+    // Flipped to the top left corner, then turned back to the top right.
+    let flipped = [
+        "-n",
+        "last",
+        "--move-numbers",
+        "--transform",
+        "flip-horizontal",
+    ];
+    assert_eq!(label(&flipped, 1), "T19");
+}
+
+#[test]
+fn game_length_counts_main_line_moves() {
+    let sgf = "(;SZ[9];B[cc];W[];B[dd](;W[ee])(;W[ff];B[gg]))(;SZ[9];AB[aa])";
+    assert_eq!(game_length(sgf, 0).unwrap(), 4);
+    assert_eq!(game_length(sgf, 1).unwrap(), 0);
+    assert!(matches!(
+        game_length(sgf, 2),
+        Err(MakeSvgError::MissingGame)
+    ));
+
+    let options = options_from(&["-n", "9999"]);
+    assert!(matches!(
+        make_svg(sgf, &options),
+        Err(MakeSvgError::NodeOutOfRange(9999, 4))
+    ));
+}
+
+#[test]
+fn last_captures_only_cover_the_current_move() {
+    let sgf = "(;SZ[9];B[cc];W[dc];B[cd];W[dd];B[ed];W[ce];B[de];W[bd];B[ec];W[bc];B[db];W[ee])";
+    let goban = Goban::from_sgf(sgf, &"11".parse().unwrap()).unwrap();
+    assert_eq!(goban.last_captures(), [(3, 2), (3, 3)]);
+    let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+    assert!(goban.last_captures().is_empty());
+}
+
+#[test]
+fn padding_surrounds_the_diagram_equally() {
+    let sgf = "(;SZ[9];B[cc])";
+    let (width, height) = options_from(&[]).output_dimensions(sgf).unwrap();
+    let options = options_from(&["--padding", "1"]);
+    let (padded_width, padded_height) = options.output_dimensions(sgf).unwrap();
+    let svg = make_svg(sgf, &options).unwrap();
+    let transform = find_by_id(&svg, "diagram")
+        .unwrap()
+        .attr("transform")
+        .unwrap();
+    let offset: f64 = transform["translate(".len()..transform.find(',').unwrap()]
+        .parse()
+        .unwrap();
+    assert!(offset > 0.0, "{}", transform);
+    assert!(transform.starts_with(&format!("translate({}, {})", offset, offset)));
+    assert!((padded_width - width - 2.0 * offset).abs() < 1e-3);
+    assert!((padded_height - height - 2.0 * offset).abs() < 1e-3);
+}
+
+#[test]
+fn parity_tint_distinguishes_odd_and_even_moves() {
+    let style = options_from(&[]).style;
+    for color in [Some(StoneColor::Black), Some(StoneColor::White), None] {
+        let odd = style.parity_move_number_color(color, 1);
+        let even = style.parity_move_number_color(color, 2);
+        assert_ne!(odd, even, "{:?}", color);
+        assert_eq!(odd, style.parity_move_number_color(color, 3));
+        assert_ne!(odd, style.move_number_color(color));
+    }
+}
+
+#[test]
+fn long_labels_are_truncated_and_shrunk() {
+    let sgf = "(;SZ[9]LB[ee:hello][cc:A])";
+    let label_texts = |args: &[&str]| {
+        let svg = make_svg(sgf, &options_from(args)).unwrap();
+        let labels = find_by_id(&svg, "markup-labels").unwrap();
+        labels
+            .children()
+            .map(|label| {
+                let text = text_child(label);
+                (text.text(), text.attr("font-size").map(str::to_string))
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        label_texts(&[]),
+        [("A".to_string(), None), ("he".to_string(), None)]
+    );
+    let texts = label_texts(&["--max-label-chars", "3", "--label-ellipsis"]);
+    assert_eq!(texts[0], ("A".to_string(), None));
+    assert_eq!(texts[1].0, "hel…");
+    assert!(texts[1].1.is_some());
+    assert_eq!(label_texts(&["--max-label-chars", "9"])[1].0, "hello");
+}
+
+#[test]
+fn move_trail_skips_captured_stones() {
+    // White's stones at D7 and D6 are captured by the last move.
+    let sgf = "(;SZ[9];B[cc];W[dc];B[cd];W[dd];B[ed];W[ce];B[de];W[bd];B[ec];W[bc];B[db])";
+    let svg = make_svg(
+        sgf,
+        &options_from(&["-n", "last", "--move-numbers", "--move-trail"]),
+    )
+    .unwrap();
+    let trail = find_by_id(&svg, "move-trail").unwrap();
+    let polyline = trail.children().next().unwrap();
+    assert_eq!(
+        polyline.attr("points"),
+        Some("2,2 2,3 4,3 2,4 3,4 1,3 4,2 1,2 3,1")
+    );
+}
+
+#[test]
+fn overlay_has_no_grid_or_background() {
+    let sgf = "(;SZ[19];B[pd]LB[dd:A]MA[pp])";
+    let options = options_from(&[
+        "-n",
+        "last",
+        "--no-grid-lines",
+        "--no-hoshi",
+        "--transparent-background",
+    ]);
+    let svg = make_svg(sgf, &options).unwrap();
+    fn names(element: &minidom::Element, found: &mut Vec<String>) {
+        found.push(element.name().to_string());
+        for child in element.children() {
+            names(child, found);
+        }
+    }
+    let mut found = vec![];
+    names(find_by_id(&svg, "goban").unwrap(), &mut found);
+    // The only lines are the two strokes of the mark.
+    assert_eq!(found.iter().filter(|name| *name == "line").count(), 2);
+    assert!(find_by_id(&svg, "lines").is_none());
+    assert!(find_by_id(&svg, "hoshi").is_none());
+    assert!(!found.iter().any(|name| name == "rect"));
+    assert!(!svg.children().any(|child| child.name() == "rect"));
+}
+
+#[test]
+fn colorized_labels_match_by_text() {
+    let label_fills = |sgf: &str, args: &[&str]| {
+        let svg = make_svg(sgf, &options_from(args)).unwrap();
+        find_by_id(&svg, "markup-labels")
+            .unwrap()
+            .children()
+            .map(|label| {
+                let text = text_child(label);
+                (text.text(), text.attr("fill").unwrap().to_string())
+            })
+            .collect::<Vec<_>>()
+    };
+    let sgf = "(;SZ[9];B[cc]LB[cc:a][ee:a][gg:b])";
+    let fills = label_fills(sgf, &["-n", "last", "--colorize-labels"]);
+    assert_eq!(fills[0].1, fills[1].1);
+    assert_ne!(fills[0].1, fills[2].1);
+    // The color depends only on the text, not the diagram.
+    let other = label_fills("(;SZ[13]LB[jj:b])", &["--colorize-labels"]);
+    assert_eq!(other[0].1, fills[2].1);
+    // Without the option, labels on empty points share the markup color.
+    let plain = label_fills(sgf, &["-n", "last"]);
+    assert_eq!(plain[1].1, plain[2].1);
+}
+
+#[test]
+fn move_number_property_renumbers_moves() {
+    let numbers = |sgf: &str| {
+        let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+        goban.moves().map(|(n, _)| n).collect::<Vec<_>>()
+    };
+    // On a node without a move, the following move is numbered on from it.
+    assert_eq!(numbers("(;SZ[9];B[aa];W[bb];MN[10];B[cc])"), [1, 2, 11]);
+    // On a node with a move, that move gets the number, before or after it in the node.
+    assert_eq!(numbers("(;SZ[9];B[aa];MN[10]W[bb];B[cc])"), [1, 10, 11]);
+    assert_eq!(numbers("(;SZ[9];B[aa];W[bb]MN[10];B[cc])"), [1, 10, 11]);
+    // A move off the board isn't recorded, so MN there doesn't renumber the move before it.
+    assert_eq!(numbers("(;SZ[9];B[aa];W[kk]MN[5];B[cc])"), [1, 6]);
+    assert!(make_svg("(;SZ[9];B[kk]MN[3])", &options_from(&["-n", "last"])).is_ok());
+
+    let sgf = "(;SZ[9];B[aa];W[bb]MN[10];B[cc])";
+    let svg = make_svg(sgf, &options_from(&["-n", "last", "--move-numbers"])).unwrap();
+    let numbers = find_by_id(&svg, "move-numbers").unwrap();
+    let texts: Vec<String> = numbers
+        .children()
+        .map(|number| text_child(number).text())
+        .collect();
+    assert_eq!(texts, ["1", "10", "11"]);
+}
+
+#[test]
+fn move_number_color_range_reaches_largest_number() {
+    let options = options_from(&["--move-number-color", "1-18446744073709551615:red"]);
+    assert_eq!(options.move_number_color_ranges[0].0, 1..u64::MAX);
+}
+
+#[test]
+fn stylesheet_shrinks_full_board() {
+    let sgf = std::fs::read_to_string("tests/data/full_board/input.sgf").unwrap();
+    let size = |args: &[&str]| {
+        let svg = make_svg(&sgf, &options_from(args)).unwrap();
+        let mut buffer = vec![];
+        svg.write_to(&mut buffer).unwrap();
+        (svg, buffer.len())
+    };
+    let (_, inline_size) = size(&["-n", "last"]);
+    let (svg, stylesheet_size) = size(&["-n", "last", "--no-inline-styles"]);
+    assert!(
+        stylesheet_size * 10 < inline_size * 9,
+        "{} vs {}",
+        stylesheet_size,
+        inline_size
+    );
+    let style = svg.children().next().unwrap();
+    assert_eq!(style.name(), "style");
+    let stones = find_by_id(&svg, "stones").unwrap();
+    for stone in stones.children() {
+        assert!(stone.attr("class").is_some());
+        assert!(stone.attr("fill").is_none());
+    }
+}
+
+#[test]
+fn dead_stones_are_faded() {
+    let sgf = "(;SZ[9]AB[cc][dd]AW[ee])";
+    let svg = make_svg(sgf, &options_from(&["--dead-stones", "dd,ee"])).unwrap();
+    let opacities: Vec<Option<&str>> = find_by_id(&svg, "stones")
+        .unwrap()
+        .children()
+        .map(|stone| stone.attr("opacity"))
+        .collect();
+    assert_eq!(opacities[0], None);
+    assert!(opacities[1].is_some());
+    assert_eq!(opacities[1], opacities[2]);
+}
+
+#[test]
+fn coordinate_precision_limits_decimals() {
+    fn max_decimals(element: &minidom::Element) -> usize {
+        let in_attrs = element
+            .attrs()
+            .filter(|(name, _)| {
+                ["x", "y", "cx", "cy", "width", "height", "points", "d"].contains(name)
+            })
+            .flat_map(|(_, value)| {
+                value
+                    .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .filter_map(|token| token.split_once('.').map(|(_, decimals)| decimals.len()))
+                    .collect::<Vec<_>>()
+            })
+            .max()
+            .unwrap_or(0);
+        element
+            .children()
+            .map(max_decimals)
+            .max()
+            .unwrap_or(0)
+            .max(in_attrs)
+    }
+    // The check mark for --correct is a path, whose numbers follow command letters.
+    let sgf = "(;SZ[19];B[pd]TR[pd][dd]CR[pp]SQ[dp];W[dd])";
+    let with_precision = |precision: Option<&str>| {
+        let mut args = vec!["-n", "last", "--correct", "cc"];
+        if let Some(precision) = precision {
+            args.extend(["--coordinate-precision", precision]);
+        }
+        make_svg(sgf, &options_from(&args)).unwrap()
+    };
+    assert_eq!(max_decimals(&with_precision(None)), 2);
+    assert_eq!(max_decimals(&with_precision(Some("1"))), 1);
+    let svg = with_precision(Some("0"));
+    assert_eq!(max_decimals(&svg), 0);
+    // Sizes smaller than a cell aren't rounded away.
+    let stone = find_by_id(&svg, "stones")
+        .unwrap()
+        .children()
+        .next()
+        .unwrap();
+    assert_eq!(stone.attr("r"), Some("0.48"));
+    let lines = find_by_id(&svg, "lines").unwrap();
+    assert_eq!(lines.attr("stroke-width"), Some("0.03"));
+
+    // Nor are opacities, em offsets or the diagram's scale.
+    let args = ["-n", "last", "--move-numbers", "--stone-opacity", "0.4"];
+    let unrounded = make_svg(sgf, &options_from(&args)).unwrap();
+    let rounded = make_svg(
+        sgf,
+        &options_from(&[&args[..], &["--coordinate-precision", "0"]].concat()),
+    )
+    .unwrap();
+    let diagram_transform = |svg: &minidom::Element| {
+        find_by_id(svg, "diagram")
+            .unwrap()
+            .attr("transform")
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(diagram_transform(&rounded), diagram_transform(&unrounded));
+    let stones = find_by_id(&rounded, "stones").unwrap();
+    assert_eq!(stones.attr("fill-opacity"), Some("0.4"));
+    let number = text_child(
+        find_by_id(&rounded, "move-numbers")
+            .unwrap()
+            .children()
+            .next()
+            .unwrap(),
+    );
+    assert_eq!(number.attr("dy"), Some("0.35em"));
+}
+
+#[test]
+fn markup_at_collects_everything_at_a_point() {
+    let sgf = "(;SZ[9];B[cc];W[dc];B[cd];W[dd];B[ed];W[ce];B[de];W[bd];B[ec];W[bc];B[db];W[dc]MA[dc]TR[dc][aa]LB[dc:x]DD[ee])";
+    let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+    assert_eq!(
+        goban.markup_at((3, 2)),
+        MarkupSet {
+            mark: true,
+            triangle: true,
+            label: Some("x".to_string()),
+            move_numbers: vec![2, 12],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        goban.markup_at((4, 4)),
+        MarkupSet {
+            dimmed: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(goban.markup_at((8, 8)), MarkupSet::default());
+}
+
+#[test]
+fn number_only_moves_keeps_real_numbers() {
+    let sgf = "(;SZ[9];B[aa];W[bb];B[cc];W[dd];B[ee])";
+    let options = options_from(&["-n", "last", "--move-numbers", "--number-only", "3,5"]);
+    let svg = make_svg(sgf, &options).unwrap();
+    let texts: Vec<String> = find_by_id(&svg, "move-numbers")
+        .unwrap()
+        .children()
+        .map(|number| text_child(number).text())
+        .collect();
+    assert_eq!(texts, ["3", "5"]);
+    assert_eq!(find_by_id(&svg, "stones").unwrap().children().count(), 5);
+}
+
+#[test]
+fn hoshi_depend_on_style() {
+    let sgf = "(;SZ[19];B[aa])";
+    let minimalist = make_svg(sgf, &options_from(&["--style", "minimalist"])).unwrap();
+    assert!(find_by_id(&minimalist, "lines").is_some());
+    assert!(find_by_id(&minimalist, "hoshi").is_none());
+
+    let fancy = make_svg(sgf, &options_from(&["--style", "fancy"])).unwrap();
+    let hoshi = find_by_id(&fancy, "hoshi").unwrap();
+    assert_eq!(hoshi.attr("fill"), Some("url(#hoshi-fill)"));
+    assert_eq!(hoshi.children().count(), 9);
+    assert!(hoshi.children().all(|dot| dot.attr("r") == Some("0.11")));
+}
+
+#[test]
+fn off_board_points_are_dropped() {
+    let sgf = "(;SZ[9]AB[cc][jc];B[zz];W[dd]MA[ee][sa]LN[aa:ja][aa:bb]LB[ee:A][ke:B])";
+    let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+    assert_eq!(
+        goban.dropped_points(),
+        [(9, 2), (25, 25), (18, 0), (9, 0), (10, 4)]
+    );
+    assert_eq!(goban.stones().count(), 2);
+    assert_eq!(goban.move_number(), 1);
+    assert_eq!(goban.marks().collect::<Vec<_>>(), [(4, 4)]);
+    assert_eq!(goban.lines().collect::<Vec<_>>(), [((0, 0), (1, 1))]);
+    assert_eq!(goban.labels().count(), 1);
+
+    assert!(make_svg(sgf, &options_from(&["-n", "last"])).is_ok());
+    assert!(matches!(
+        make_svg(sgf, &options_from(&["-n", "last", "--strict"])),
+        Err(MakeSvgError::PointOffBoard((9, 2)))
+    ));
+    assert!(matches!(
+        make_ascii(sgf, &options_from(&["-n", "last", "--strict"])),
+        Err(MakeSvgError::PointOffBoard((9, 2)))
+    ));
+    #[cfg(feature = "json")]
+    assert!(matches!(
+        sgf_render::make_json(sgf, &options_from(&["-n", "last", "--strict"])),
+        Err(MakeSvgError::PointOffBoard((9, 2)))
+    ));
+}
+
+#[test]
+fn narrow_diagrams_are_simplified() {
+    let sgf = "(;SZ[9];B[cc]LB[dd:A];W[ee]MA[cc]C[Hello])";
+    let args = [
+        "-n",
+        "last",
+        "--move-numbers",
+        "--show-comment",
+        "--simplify-below",
+        "200",
+    ];
+    let thumbnail = make_svg(sgf, &options_from(&[&args[..], &["-w", "150"]].concat())).unwrap();
+    assert_eq!(
+        find_by_id(&thumbnail, "stones").unwrap().children().count(),
+        2
+    );
+    assert!(find_by_id(&thumbnail, "lines").is_some());
+    for id in [
+        "move-numbers",
+        "markup-labels",
+        "markup-marks",
+        "board-labels",
+        "caption",
+    ] {
+        assert!(find_by_id(&thumbnail, id).is_none(), "{} drawn", id);
+    }
+
+    let full = make_svg(sgf, &options_from(&args)).unwrap();
+    for id in ["move-numbers", "markup-labels", "board-labels", "caption"] {
+        assert!(find_by_id(&full, id).is_some(), "{} missing", id);
+    }
+}
+
+#[test]
+fn stone_colors_contrast_with_their_opposite() {
+    assert_eq!(StoneColor::Black.opposite(), StoneColor::White);
+    assert_eq!(StoneColor::White.opposite(), StoneColor::Black);
+    assert_eq!(StoneColor::Black.contrast_color(), "white");
+    assert_eq!(StoneColor::White.contrast_color(), "black");
+    assert_eq!(StoneColor::Black.color(), "black");
+
+    let sgf = "(;SZ[9];B[cc];W[ee]TB[aa]TW[ii]LB[cc:A][ee:B])";
+    let svg = make_svg(sgf, &options_from(&["-n", "last", "--territory"])).unwrap();
+    let colors: Vec<_> = find_by_id(&svg, "territory")
+        .unwrap()
+        .children()
+        .map(|square| (square.attr("fill").unwrap(), square.attr("stroke").unwrap()))
+        .collect();
+    assert_eq!(colors, [("black", "white"), ("white", "black")]);
+    let label_fills: Vec<_> = find_by_id(&svg, "markup-labels")
+        .unwrap()
+        .children()
+        .map(|label| text_child(label).attr("fill").unwrap())
+        .collect();
+    assert_eq!(label_fills, ["white", "black"]);
+}
+
+#[test]
+fn layered_svg_tags_each_move() {
+    let sgf = "(;SZ[9]AB[aa];B[cc];W[dd];B[ee])";
+    let svg = make_layered_svg(sgf, &options_from(&["-n", "last", "--move-numbers"])).unwrap();
+    for n in 1..=3 {
+        let stone = find_by_id(&svg, &format!("move-{}-stone", n)).unwrap();
+        let number = find_by_id(&svg, &format!("move-{}-number", n)).unwrap();
+        assert_eq!(stone.attr("data-move"), Some(n.to_string().as_str()));
+        assert_eq!(number.attr("data-move"), Some(n.to_string().as_str()));
+    }
+    let stones = find_by_id(&svg, "stones").unwrap();
+    assert_eq!(
+        stones
+            .children()
+            .filter(|stone| stone.attr("data-move").is_none())
+            .count(),
+        1
+    );
+
+    let plain = make_svg(sgf, &options_from(&["-n", "last", "--move-numbers"])).unwrap();
+    assert!(find_by_id(&plain, "move-1-stone").is_none());
+}
+
+#[test]
+fn verbose_results_are_spelled_out() {
+    let cases = [
+        ("W+R", "White wins by resignation"),
+        ("B+Resign", "Black wins by resignation"),
+        ("W+T", "White wins on time"),
+        ("B+Forfeit", "Black wins by forfeit"),
+        ("B+3.5", "Black wins by 3.5 points"),
+        ("W+1", "White wins by 1 point"),
+        ("B+", "Black wins"),
+        ("0", "Draw"),
+        ("Void", "No result"),
+        ("W+lots", "W+lots"),
+    ];
+    for (result, expected) in cases.iter().copied() {
+        assert_eq!(ResultFormat::Verbose.format(result), expected);
+        assert_eq!(ResultFormat::Raw.format(result), result);
+    }
+
+    let sgf = "(;SZ[9]PB[Honinbo]RE[W+R];B[cc])";
+    let options = options_from(&["-n", "last", "--result-stamp", "--result-format", "verbose"]);
+    let svg = make_svg(sgf, &options).unwrap();
+    let stamp = find_by_id(&svg, "result-stamp").unwrap();
+    let text = text_child(stamp);
+    assert_eq!(text.text(), "White wins by resignation");
+}
+
+#[test]
+fn lines_thin_out_on_oversized_boards() {
+    let stroke_width = |sgf: &str| {
+        let svg = make_svg(sgf, &options_from(&["--thin-lines-above", "19"])).unwrap();
+        find_by_id(&svg, "lines")
+            .unwrap()
+            .attr("stroke-width")
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(stroke_width("(;SZ[19])"), "0.03");
+    assert_eq!(stroke_width("(;SZ[38])"), "0.015");
+    assert_eq!(stroke_width("(;SZ[38:19])"), "0.015");
+}
+
+#[test]
+fn game_positions_follow_the_main_line() {
+    let sgf = "(;SZ[9];B[cc];W[dd]C[Setup next];AB[ee];B[];W[ff](;B[gg])(;B[hh]))";
+    let positions: Vec<Goban> = game_positions(sgf, 0)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(positions.len() as u64, game_length(sgf, 0).unwrap());
+    let move_numbers: Vec<u64> = positions.iter().map(Goban::move_number).collect();
+    assert_eq!(move_numbers, [1, 2, 3, 4, 5]);
+    assert_eq!(positions[0].stones().count(), 1);
+    assert_eq!(positions[4].stones().count(), 5);
+    assert_eq!(positions[4].last_move(), Some((6, 6)));
+    assert!(positions[4].is_last_node());
+    assert!(!positions[3].is_last_node());
+
+    let errors: Vec<_> = game_positions("(;B[aa];W[aa];B[bb])", 0).unwrap().collect();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[1], Err(MakeSvgError::InvalidMoveError)));
+    assert!(matches!(
+        game_positions("(;B[aa])", 1),
+        Err(MakeSvgError::MissingGame)
+    ));
+}
+
+#[test]
+fn background_markup_is_drawn_beneath_stones() {
+    let sgf = "(;SZ[9];B[cc]SQ[cc][dd]TR[ee])";
+    let svg = make_svg(
+        sgf,
+        &options_from(&["-n", "last", "--background-markup", "square"]),
+    )
+    .unwrap();
+    let goban = find_by_id(&svg, "goban").unwrap();
+    let ids: Vec<&str> = goban
+        .children()
+        .filter_map(|child| child.attr("id"))
+        .collect();
+    let position = |id: &str| ids.iter().position(|&other| other == id).unwrap();
+    assert!(position("markup-squares") < position("stones"));
+    assert!(position("markup-triangles") > position("stones"));
+
+    // The square on the stone is hidden by it entirely.
+    let stone = find_by_id(&svg, "stones")
+        .unwrap()
+        .children()
+        .next()
+        .unwrap();
+    let stone_radius: f64 = stone.attr("r").unwrap().parse().unwrap();
+    let square = find_by_id(&svg, "markup-squares")
+        .unwrap()
+        .children()
+        .flat_map(|group| group.children())
+        .find(|rect| rect.attr("x") == Some("1.725"))
+        .unwrap();
+    let side: f64 = square.attr("width").unwrap().parse().unwrap();
+    let stroke_width = 0.03;
+    assert!(side / 2.0 * 2f64.sqrt() + stroke_width / 2.0 < stone_radius);
+}
+
+#[test]
+fn number_patches_match_the_board() {
+    let sgf = "(;SZ[9];B[cc];W[dd];AE[cc]LB[ee:A])";
+    let patch_fills = |args: &[&str]| {
+        let svg = make_svg(sgf, &options_from(args)).unwrap();
+        ["move-numbers", "markup-labels"]
+            .iter()
+            .map(|id| {
+                let patch = find_by_id(&svg, id)
+                    .unwrap()
+                    .children()
+                    .flat_map(|group| group.children())
+                    .find(|child| child.name() != "text")
+                    .unwrap();
+                (
+                    patch.attr("fill").unwrap().to_string(),
+                    patch.attr("fill-opacity").map(str::to_string),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let dark = patch_fills(&["-n", "last", "--move-numbers", "--style", "dark"]);
+    assert_eq!(dark, vec![("#2b2b2b".to_string(), None); 2]);
+    let wood = patch_fills(&["-n", "last", "--move-numbers", "--board-texture", "wood"]);
+    assert_eq!(
+        wood,
+        vec![("#ddb165".to_string(), Some("0.7".to_string())); 2]
+    );
+}
+
+const RENDER_SGF: &str = "(;SZ[9];B[cc];W[gg]TR[cc])";
+
+fn render_format(format: OutputFormat) -> Vec<u8> {
+    let options = options_from(&["-n", "last", "--width", "90"]);
+    let output = sgf_render::render(RENDER_SGF, &options, format).unwrap();
+    assert!(!output.is_empty());
+    output
+}
+
+#[test]
+fn render_svg() {
+    let svg = String::from_utf8(render_format(OutputFormat::Svg)).unwrap();
+    let element: minidom::Element = svg.parse().unwrap();
+    assert_eq!(element.name(), "svg");
+}
+
+#[test]
+fn render_ascii() {
+    let ascii = String::from_utf8(render_format(OutputFormat::Ascii)).unwrap();
+    assert_eq!(
+        ascii,
+        make_ascii(RENDER_SGF, &options_from(&["-n", "last"])).unwrap()
+    );
+    assert_eq!(ascii.lines().count(), 9);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn render_png() {
+    assert!(render_format(OutputFormat::Png).starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+#[cfg(feature = "gif")]
+#[test]
+fn render_gif() {
+    assert!(render_format(OutputFormat::Gif).starts_with(b"GIF89a"));
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn render_pdf() {
+    let pdf = render_format(OutputFormat::Pdf);
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.ends_with(b"%%EOF") || pdf.ends_with(b"%%EOF\n"));
+}
+
+#[cfg(feature = "webp")]
+#[test]
+fn render_webp() {
+    let webp = render_format(OutputFormat::Webp);
+    assert_eq!(&webp[..4], b"RIFF");
+    assert_eq!(&webp[8..12], b"WEBP");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn render_json() {
+    let json = String::from_utf8(render_format(OutputFormat::Json)).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["size"], serde_json::json!([9, 9]));
+}
+
+#[test]
+fn collection_renders_match_make_svg() {
+    let sgf = "(;SZ[9];B[cc];W[gg])(;SZ[13];B[kk])";
+    let collection = sgf_parse::go::parse(sgf).unwrap();
+    for game in ["0", "1"] {
+        let options = options_from(&["--game", game]);
+        assert_eq!(
+            make_svg_from_collection(&collection, &options).unwrap(),
+            make_svg(sgf, &options).unwrap()
+        );
+    }
+    let options = options_from(&["--game", "2"]);
+    assert!(matches!(
+        make_svg_from_collection(&collection, &options),
+        Err(MakeSvgError::MissingGame)
+    ));
+}
+
+#[test]
+fn solution_marks_ignore_the_style() {
+    let sgf = "(;SZ[9]AB[cc]AW[dd])";
+    let mut strokes = vec![];
+    for style in ["simple", "fancy", "minimalist"] {
+        let svg = make_svg(
+            sgf,
+            &options_from(&["--style", style, "--correct", "cc", "--wrong", "dd,ee"]),
+        )
+        .unwrap();
+        let goban = find_by_id(&svg, "goban").unwrap();
+        let ids: Vec<&str> = goban
+            .children()
+            .filter_map(|child| child.attr("id"))
+            .collect();
+        let position = |id: &str| ids.iter().position(|&other| other == id).unwrap();
+        assert!(position("solutions") > position("stones"));
+        let marks: Vec<String> = find_by_id(&svg, "solutions")
+            .unwrap()
+            .children()
+            .map(|mark| mark.attr("stroke").unwrap().to_string())
+            .collect();
+        assert_eq!(marks.len(), 3);
+        assert_ne!(marks[0], marks[1]);
+        assert_eq!(marks[1], marks[2]);
+        strokes.push(marks);
+    }
+    assert!(strokes.windows(2).all(|pair| pair[0] == pair[1]));
+}
+
+#[test]
+fn offboard_annotations_can_be_trimmed() {
+    let sgf = "(;SZ[9]AR[aa:cc][bb:gg]LN[ab:bc][bd:hh])";
+    let count = |id: &str, svg: &minidom::Element| find_by_id(svg, id).unwrap().children().count();
+    let svg = make_svg(sgf, &options_from(&["--range", "aa-ee"])).unwrap();
+    assert_eq!(count("markup-arrows", &svg), 2);
+    assert_eq!(count("markup-lines", &svg), 2);
+
+    let svg = make_svg(
+        sgf,
+        &options_from(&["--range", "aa-ee", "--trim-offboard-annotations"]),
+    )
+    .unwrap();
+    let arrows = find_by_id(&svg, "markup-arrows").unwrap();
+    assert_eq!(arrows.children().count(), 1);
+    let arrow = arrows.children().next().unwrap();
+    assert_eq!((arrow.attr("x2"), arrow.attr("y2")), (Some("2"), Some("2")));
+    assert_eq!(count("markup-lines", &svg), 1);
+}
+
+#[test]
+fn stone_fill_overrides_the_style() {
+    let sgf = "(;SZ[9]AB[cc]AW[dd][ee])";
+    let svg = make_svg(
+        sgf,
+        &options_from(&["--style", "simple", "--stone-fill", "dd:#c33"]),
+    )
+    .unwrap();
+    let stones: Vec<_> = find_by_id(&svg, "stones").unwrap().children().collect();
+    assert_eq!(stones[0].attr("fill"), Some("black"));
+    assert_eq!(stones[1].attr("fill"), Some("#c33"));
+    assert_eq!(stones[1].attr("stroke"), stones[2].attr("stroke"));
+    assert!(stones[1].attr("stroke").is_some());
+    assert_eq!(stones[2].attr("fill"), Some("white"));
+
+    let options = MakeSvgArgs::try_parse_from(["sgf-render", "--stone-fill", "dd"]);
+    assert!(options.is_err());
+}
+
+#[test]
+fn info_box_omits_missing_properties() {
+    let rows = |sgf: &str| -> Vec<String> {
+        let svg = make_svg(sgf, &options_from(&["--info-box"])).unwrap();
+        find_by_id(&svg, "info-box")
+            .unwrap()
+            .children()
+            .filter(|child| child.name() == "text")
+            .map(|text| text.text())
+            .collect()
+    };
+    assert_eq!(
+        rows("(;SZ[13]KM[0.5]HA[2]RU[AGA])"),
+        ["Komi", "0.5", "Handicap", "2", "Rules", "AGA", "Size", "13×13"]
+    );
+    assert_eq!(rows("(;SZ[19:9])"), ["Size", "19×9"]);
+    assert_eq!(
+        game_info("(;RU[Chinese])").unwrap().ruleset.as_deref(),
+        Some("Chinese")
+    );
+    let svg = make_svg("(;KM[6.5])", &options_from(&[])).unwrap();
+    assert!(find_by_id(&svg, "info-box").is_none());
+}
+
+#[test]
+fn figures_written_to_files() {
+    let sgf = "(;SZ[9];B[cc];W[gg]FG[];B[dd];W[ee];B[ff]FG[];W[hh])";
+    let options = options_from(&[]);
+    let dir = std::env::temp_dir().join(format!("sgf-render-figures-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let results = render_figures_to_files(sgf, &options, &dir, |i| match i {
+        1 => "missing/figure-2.svg".to_string(),
+        _ => format!("figure-{}.svg", i + 1),
+    })
+    .unwrap();
+    let figures = make_figures(sgf, &options).unwrap();
+    assert_eq!(results.len(), figures.len());
+    assert!(matches!(results[1], Err(MakeSvgError::FileError(_, _))));
+    for i in [0, 2] {
+        let path = results[i].as_ref().unwrap();
+        assert_eq!(path, &dir.join(format!("figure-{}.svg", i + 1)));
+        let mut expected = vec![];
+        figures[i].write_to(&mut expected).unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), expected);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // A figure that fails to render only fails its own entry.
+    std::fs::create_dir_all(&dir).unwrap();
+    let strict = options_from(&["--strict"]);
+    let sgf = "(;SZ[9];B[cc];W[gg]FG[];B[dd];W[ee];B[ff]FG[];W[hh]TR[zz])";
+    let results = render_figures_to_files(sgf, &strict, &dir, |i| format!("{}.svg", i)).unwrap();
+    assert!(results[0].is_ok() && results[1].is_ok());
+    assert!(matches!(results[2], Err(MakeSvgError::PointOffBoard(_))));
+    assert!(make_figures(sgf, &strict).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(render_figures_to_files("(;B[zz", &options, &dir, |i| i.to_string()).is_err());
+}
+
+#[test]
+fn light_angle_moves_stone_highlights() {
+    let sgf = "(;SZ[9]AB[cc]AW[dd])";
+    let focus = |args: &[&str]| {
+        let svg = make_svg(sgf, &options_from(args)).unwrap();
+        ["black-stone-fill", "white-stone-fill", "hoshi-fill"]
+            .iter()
+            .map(|id| {
+                let gradient = find_by_id(&svg, id).unwrap();
+                (
+                    gradient.attr("fx").map(str::to_string),
+                    gradient.attr("fy").map(str::to_string),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let unlit = (None, None);
+    assert_eq!(
+        focus(&["--style", "fancy"]),
+        [unlit.clone(), unlit.clone(), unlit.clone()]
+    );
+    let top_left = (Some("35%".to_string()), Some("35%".to_string()));
+    assert_eq!(
+        focus(&["--style", "fancy", "--light-angle", "135"]),
+        [top_left.clone(), top_left, unlit.clone()]
+    );
+    let below = (Some("50%".to_string()), Some("71.2132%".to_string()));
+    assert_eq!(
+        focus(&["--style", "fancy", "--light-angle", "-90"]),
+        [below.clone(), below, unlit]
+    );
+}
+
+#[test]
+fn group_outlines_trace_each_group_once() {
+    let sgf = "(;SZ[9]AB[aa][ba][ca][ab][cb][ac][bc][cc]AW[ee])";
+    let svg = make_svg(sgf, &options_from(&["--outline-groups", "aa,cc,ee,gg"])).unwrap();
+    let outlines: Vec<_> = find_by_id(&svg, "group-outlines")
+        .unwrap()
+        .children()
+        .collect();
+    assert_eq!(outlines.len(), 2);
+    // The ring is outlined outside and around its eye.
+    let ring = outlines[0].attr("d").unwrap();
+    assert_eq!(ring.matches('M').count(), 2);
+    assert!(ring.starts_with("M-0.5,-0.25 Q-0.5,-0.5 -0.25,-0.5 L2.25,-0.5"));
+    assert!(ring.contains("M0.75,0.5 Q0.5,0.5 0.5,0.75"));
+    assert_eq!(
+        outlines[1].attr("d"),
+        Some("M3.5,3.75 Q3.5,3.5 3.75,3.5 L4.25,3.5 Q4.5,3.5 4.5,3.75 L4.5,4.25 Q4.5,4.5 4.25,4.5 L3.75,4.5 Q3.5,4.5 3.5,4.25 Z")
+    );
+
+    let goban = Goban::from_sgf(sgf, &"last".parse().unwrap()).unwrap();
+    assert_eq!(goban.group((1, 0)).unwrap().len(), 8);
+    assert_eq!(goban.group((1, 1)), None);
+}
+
+#[test]
+fn errors_describe_and_chain_their_causes() {
+    use std::error::Error;
+
+    let options = options_from(&[]);
+    let error = make_svg("(;B[aa];W[aa])", &options_from(&["-n", "last"])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid move: the point is already occupied."
+    );
+    assert!(error.source().is_none());
+
+    let error = make_svg("(;B[aa]", &options).unwrap_err();
+    assert!(matches!(error, MakeSvgError::ParseError(_, _)));
+    // The cause is left to `source()` rather than repeated in the message.
+    let cause = error.source().unwrap();
+    assert!(error.to_string().starts_with("Invalid SGF at line 1"));
+    assert!(!error.to_string().contains(&cause.to_string()));
+
+    let error = make_svg_from_reader(&[0xff, 0xfe][..], &options).unwrap_err();
+    assert_eq!(error.to_string(), "Input is not valid UTF-8");
+    assert!(error
+        .source()
+        .unwrap()
+        .downcast_ref::<std::string::FromUtf8Error>()
+        .is_some());
+
+    let path = std::env::temp_dir()
+        .join("sgf-render-missing-dir")
+        .join("x.svg");
+    let results = render_figures_to_files("(;B[aa])", &options, path.parent().unwrap(), |_| {
+        "x.svg".into()
+    })
+    .unwrap();
+    let error = results[0].as_ref().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!("Failed to write {}", path.display())
+    );
+    let cause = error.source().unwrap().downcast_ref::<std::io::Error>();
+    assert_eq!(cause.unwrap().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn cell_size_fixes_the_board_scale() {
+    let scale = |sgf: &str, args: &[&str]| {
+        let svg = make_svg(sgf, &options_from(args)).unwrap();
+        let transform = find_by_id(&svg, "diagram")
+            .unwrap()
+            .attr("transform")
+            .unwrap();
+        let width: f64 = svg.attr("width").unwrap().parse().unwrap();
+        (parse_pair(transform, "scale").0, width)
+    };
+    let (small_scale, small_width) = scale("(;SZ[9])", &["--cell-size", "40"]);
+    let (large_scale, large_width) = scale("(;SZ[19])", &["--cell-size", "40"]);
+    assert_eq!(small_scale, 40.0);
+    assert_eq!(large_scale, 40.0);
+    assert!(large_width > small_width);
+
+    let (small_scale, _) = scale("(;SZ[9])", &[]);
+    let (large_scale, _) = scale("(;SZ[19])", &[]);
+    assert!(small_scale > large_scale);
+
+    assert!(MakeSvgArgs::try_parse_from(["sgf-render", "--cell-size", "40", "-w", "300"]).is_err());
+    assert_eq!(
+        options_from(&["--cell-size", "40"]).size,
+        SizeSpec::CellSize(40.0)
+    );
+
+    // Simplifying and the comparison gap go by the width the cell size gives.
+    let thumbnail = make_svg(
+        "(;SZ[9])",
+        &options_from(&[
+            "--cell-size",
+            "10",
+            "--simplify-below",
+            "200",
+            "--label-sides",
+            "nw",
+        ]),
+    )
+    .unwrap();
+    assert!(find_by_id(&thumbnail, "board-labels").is_none());
+    let comparison = make_comparison_svg(
+        "(;SZ[9];B[cc])",
+        "0".parse().unwrap(),
+        "1".parse().unwrap(),
+        &options_from(&["--cell-size", "10"]),
+    )
+    .unwrap();
+    let panel_width: f64 = comparison
+        .children()
+        .find(|child| child.name() == "svg")
+        .and_then(|panel| panel.attr("width"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    let width: f64 = comparison.attr("width").unwrap().parse().unwrap();
+    assert!((width - panel_width * 2.05).abs() < 1e-9);
+}